@@ -0,0 +1,79 @@
+//! Activity and inactivity detection
+
+use crate::{
+    register::{mg_to_thresh, thresh_to_mg, ActInactCtl, Register},
+    Adxl343,
+};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Activity and inactivity detection configuration, applied with
+/// `Adxl343::configure_activity`
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ActivityConfig {
+    /// Activity threshold in mg (`THRESH_ACT`, 62.5 mg/LSB)
+    pub activity_threshold_mg: u16,
+
+    /// Inactivity threshold in mg (`THRESH_INACT`, 62.5 mg/LSB)
+    pub inactivity_threshold_mg: u16,
+
+    /// Time acceleration must stay below the inactivity threshold for
+    /// inactivity to be declared, in seconds (`TIME_INACT`, 1 s/LSB)
+    pub inactivity_time_s: u8,
+
+    /// Per-axis enables and AC/DC coupling (`ACT_INACT_CTL`)
+    pub control: ActInactCtl,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure activity and inactivity detection, writing `THRESH_ACT`,
+    /// `THRESH_INACT`, `TIME_INACT`, and `ACT_INACT_CTL`.
+    ///
+    /// Thresholds are rounded to the nearest 62.5 mg LSB. Thresholds above
+    /// 15.9375g don't fit in the registers and return `ErrorKind::Param`.
+    pub fn configure_activity(&mut self, config: ActivityConfig) -> Result<(), Error<E>> {
+        let activity = mg_to_thresh(config.activity_threshold_mg)
+            .ok_or_else(|| Error::new(ErrorKind::Param))?;
+        let inactivity = mg_to_thresh(config.inactivity_threshold_mg)
+            .ok_or_else(|| Error::new(ErrorKind::Param))?;
+
+        self.write_register(Register::THRESH_ACT, activity)?;
+        self.write_register(Register::THRESH_INACT, inactivity)?;
+        self.write_register(Register::TIME_INACT, config.inactivity_time_s)?;
+        self.write_register(Register::ACT_INACT_CTL, config.control.bits())
+    }
+
+    /// Read the activity and inactivity detection configuration
+    pub fn activity_config(&mut self) -> Result<ActivityConfig, Error<E>> {
+        Ok(ActivityConfig {
+            activity_threshold_mg: thresh_to_mg(self.read_register(Register::THRESH_ACT)?),
+            inactivity_threshold_mg: thresh_to_mg(self.read_register(Register::THRESH_INACT)?),
+            inactivity_time_s: self.read_register(Register::TIME_INACT)?,
+            control: ActInactCtl::from_bits_truncate(self.read_register(Register::ACT_INACT_CTL)?),
+        })
+    }
+
+    /// Set the activity threshold (`THRESH_ACT`) in mg.
+    ///
+    /// Rounded to the nearest 62.5 mg LSB. Thresholds above 15.9375g don't
+    /// fit in the register and return `ErrorKind::Param`.
+    pub fn set_activity_threshold_mg(&mut self, threshold_mg: u16) -> Result<(), Error<E>> {
+        let threshold = mg_to_thresh(threshold_mg).ok_or_else(|| Error::new(ErrorKind::Param))?;
+        self.write_register(Register::THRESH_ACT, threshold)
+    }
+
+    /// Set the inactivity threshold (`THRESH_INACT`) in mg.
+    ///
+    /// Rounded to the nearest 62.5 mg LSB. Thresholds above 15.9375g don't
+    /// fit in the register and return `ErrorKind::Param`.
+    pub fn set_inactivity_threshold_mg(&mut self, threshold_mg: u16) -> Result<(), Error<E>> {
+        let threshold = mg_to_thresh(threshold_mg).ok_or_else(|| Error::new(ErrorKind::Param))?;
+        self.write_register(Register::THRESH_INACT, threshold)
+    }
+}
@@ -0,0 +1,162 @@
+//! Asynchronous driver built on `embedded-hal-async` (`async` feature)
+
+use crate::{init_registers, register::Register, DataFormatFlags, DataRate, ADDRESS, DEVICE_ID};
+#[cfg(feature = "i16x3")]
+use accelerometer::vector::{F32x3, I16x3};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal_async::i2c::I2c;
+
+/// Asynchronous ADXL343 driver, for use with async executors such as Embassy.
+///
+/// Performs the same initialization as [`crate::Adxl343`], but awaits each
+/// bus transaction rather than blocking on it.
+pub struct Adxl343Async<I2C> {
+    /// Underlying I2C device
+    i2c: I2C,
+
+    /// I2C address of the device
+    address: u8,
+
+    /// Current data format
+    data_format: DataFormatFlags,
+
+    /// Current output data rate
+    data_rate: DataRate,
+}
+
+impl<I2C, E> Adxl343Async<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Create a new asynchronous ADXL343 driver from the given I2C peripheral
+    pub async fn new(i2c: I2C) -> Result<Self, Error<E>> {
+        Self::new_with_data_format(i2c, DataFormatFlags::default()).await
+    }
+
+    /// Create a new asynchronous ADXL343 driver configured with the given
+    /// data format
+    pub async fn new_with_data_format<F>(i2c: I2C, data_format: F) -> Result<Self, Error<E>>
+    where
+        F: Into<DataFormatFlags>,
+    {
+        Self::connect(i2c, ADDRESS, data_format.into()).await
+    }
+
+    /// Create a new asynchronous ADXL343 driver for a device at the given I2C
+    /// address (see [`crate::Adxl343::new_with_address`])
+    pub async fn new_with_address(i2c: I2C, address: u8) -> Result<Self, Error<E>> {
+        Self::connect(i2c, address, DataFormatFlags::default()).await
+    }
+
+    /// Check the device ID and apply the initial configuration
+    async fn connect(
+        i2c: I2C,
+        address: u8,
+        data_format: DataFormatFlags,
+    ) -> Result<Self, Error<E>> {
+        let mut adxl343 = Adxl343Async {
+            i2c,
+            address,
+            data_format,
+            data_rate: DataRate::default(),
+        };
+
+        // Ensure we have the correct device ID for the ADLX343
+        if adxl343.read_register(Register::DEVID).await? != DEVICE_ID {
+            ErrorKind::Device.err()?;
+        }
+
+        adxl343.data_format(adxl343.data_format).await?;
+
+        // Read the current output data rate
+        let bw_rate = adxl343.read_register(Register::BW_RATE).await?;
+        adxl343.data_rate = DataRate::from_code(bw_rate);
+
+        for (register, value) in init_registers() {
+            adxl343.write_register(register, value).await?;
+        }
+
+        Ok(adxl343)
+    }
+
+    /// Set the device data format
+    pub async fn data_format<F>(&mut self, data_format: F) -> Result<(), Error<E>>
+    where
+        F: Into<DataFormatFlags>,
+    {
+        let f = data_format.into();
+        self.i2c.write(self.address, &f.register_write()).await?;
+        self.data_format = f;
+        Ok(())
+    }
+
+    /// Get the output data rate, as read from `BW_RATE` when the driver was
+    /// created
+    pub fn data_rate(&self) -> DataRate {
+        self.data_rate
+    }
+
+    /// Write to the given register.
+    ///
+    /// Returns `ErrorKind::Param` without writing to the bus if the register
+    /// is read-only, or is `DATA_FORMAT`: the data format must be set with
+    /// [`Adxl343Async::data_format`] so the driver's cached copy stays in sync.
+    pub async fn write_register(&mut self, register: Register, value: u8) -> Result<(), Error<E>> {
+        // Preserve the invariant around self.data_format, and never write to
+        // read-only registers
+        if register == Register::DATA_FORMAT || register.read_only() {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        self.i2c
+            .write(self.address, &[register.addr(), value])
+            .await?;
+        Ok(())
+    }
+
+    /// Read a single byte from the given register
+    async fn read_register(&mut self, register: Register) -> Result<u8, Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c
+            .write_read(self.address, &[register.addr()], &mut buffer)
+            .await?;
+        Ok(buffer[0])
+    }
+
+    /// Get a raw acceleration reading from a single burst read of the data
+    /// registers (see [`crate::Adxl343::accel_raw_with_endian`])
+    #[cfg(feature = "i16x3")]
+    pub async fn accel_raw(&mut self) -> Result<I16x3, Error<E>> {
+        self.data_format.check_justify(false)?;
+
+        let mut bytes = [0u8; 6];
+        self.i2c
+            .write_read(self.address, &[Register::DATAX0.addr()], &mut bytes)
+            .await?;
+
+        Ok(I16x3::new(
+            i16::from_le_bytes([bytes[0], bytes[1]]),
+            i16::from_le_bytes([bytes[2], bytes[3]]),
+            i16::from_le_bytes([bytes[4], bytes[5]]),
+        ))
+    }
+
+    /// Get normalized ±g reading from the accelerometer
+    #[cfg(feature = "i16x3")]
+    pub async fn accel_norm(&mut self) -> Result<F32x3, Error<E>> {
+        let raw = self.accel_raw().await?;
+        Ok(self.data_format.raw_to_g(raw))
+    }
+
+    /// Get the I2C address the driver is communicating with
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Consume the driver and return the underlying I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
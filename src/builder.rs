@@ -0,0 +1,130 @@
+//! Builder for configuring the device before measurement is enabled
+
+use crate::{
+    power::MEASURE, register::Register, Adxl343, DataFormatFlags, DataRate, InterruptConfig,
+    Interrupts, NoDelay, TapConfig, ADDRESS,
+};
+use accelerometer::Error;
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Builder for an [`Adxl343`] driver, allowing the device to be fully
+/// configured before measurement is enabled.
+///
+/// The defaults match [`Adxl343::new`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Adxl343Builder {
+    /// I2C address of the device
+    address: u8,
+
+    /// Data format
+    data_format: DataFormatFlags,
+
+    /// Output data rate
+    data_rate: DataRate,
+
+    /// Tap detection configuration
+    tap: TapConfig,
+
+    /// Interrupt configuration
+    interrupts: InterruptConfig,
+}
+
+impl Default for Adxl343Builder {
+    fn default() -> Adxl343Builder {
+        Adxl343Builder {
+            address: ADDRESS,
+            data_format: DataFormatFlags::default(),
+            data_rate: DataRate::default(),
+            tap: TapConfig::default(),
+            interrupts: InterruptConfig::default(),
+        }
+    }
+}
+
+impl Adxl343Builder {
+    /// Create a new builder with the default configuration
+    pub fn new() -> Adxl343Builder {
+        Adxl343Builder::default()
+    }
+
+    /// Set the I2C address of the device (see [`Adxl343::new_with_address`])
+    pub fn address(mut self, address: u8) -> Adxl343Builder {
+        self.address = address;
+        self
+    }
+
+    /// Set the data format.
+    ///
+    /// The `INT_INVERT` bit is determined by the interrupt configuration.
+    pub fn data_format<F>(mut self, data_format: F) -> Adxl343Builder
+    where
+        F: Into<DataFormatFlags>,
+    {
+        self.data_format = data_format.into();
+        self
+    }
+
+    /// Set the output data rate
+    pub fn data_rate(mut self, data_rate: DataRate) -> Adxl343Builder {
+        self.data_rate = data_rate;
+        self
+    }
+
+    /// Set the tap detection configuration
+    pub fn tap(mut self, tap: TapConfig) -> Adxl343Builder {
+        self.tap = tap;
+        self
+    }
+
+    /// Set the interrupt configuration
+    pub fn interrupts(mut self, interrupts: InterruptConfig) -> Adxl343Builder {
+        self.interrupts = interrupts;
+        self
+    }
+
+    /// Check the device ID, apply the configuration, and enable measurement.
+    ///
+    /// The device is placed in standby while it is configured, so no samples
+    /// are taken (and no interrupts raised) with a partial configuration.
+    pub fn build<I2C, E>(self, i2c: I2C) -> Result<Adxl343<I2C>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        E: Debug,
+    {
+        self.build_with_delay(i2c, NoDelay)
+    }
+
+    /// Build a driver which uses the given delay to wait for the device to
+    /// settle after configuration changes (see [`Adxl343::new_with_delay`]),
+    /// otherwise as [`Adxl343Builder::build`]
+    pub fn build_with_delay<I2C, DELAY, E>(
+        self,
+        i2c: I2C,
+        delay: DELAY,
+    ) -> Result<Adxl343<I2C, DELAY>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        DELAY: DelayNs,
+        E: Debug,
+    {
+        let mut data_format = self.data_format;
+        data_format.set(DataFormatFlags::INT_INVERT, self.interrupts.active_low);
+
+        let mut adxl343 = Adxl343::probe(i2c, self.address, data_format, delay)?;
+
+        // Interrupt outputs stay disabled until everything else, including
+        // their polarity, is configured (see `Adxl343::configure_interrupts`)
+        adxl343.write_register(Register::POWER_CTL, 0)?;
+        adxl343.set_interrupts(Interrupts::empty())?;
+        adxl343.data_format(data_format)?;
+        adxl343.set_data_rate(self.data_rate)?;
+        adxl343.configure_tap(self.tap)?;
+        adxl343.map_interrupts(self.interrupts.int2_routed)?;
+        adxl343.set_interrupts(self.interrupts.enabled)?;
+        adxl343.write_register(Register::POWER_CTL, MEASURE)?;
+        adxl343.settle();
+
+        Ok(adxl343)
+    }
+}
@@ -0,0 +1,110 @@
+//! Bump counter built on activity detection
+
+use crate::{
+    register::{ActInactCtl, Interrupts, Register},
+    Adxl343,
+};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Debounce state for the bump counter
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct BumpCounter {
+    /// Time after a bump during which further activity is treated as bounce,
+    /// in microseconds
+    refractory_us: u64,
+
+    /// Time at which the last bump was counted, in microseconds
+    last_bump_us: Option<u64>,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure activity detection on the vertical (Z) axis for use as a
+    /// bump counter, and reset its debounce state.
+    ///
+    /// `threshold_g` is the change in acceleration which registers as a bump.
+    /// Detection is AC-coupled, so it is relative to the resting acceleration
+    /// rather than to 0g. It is programmed into `THRESH_ACT` at 62.5 mg/LSB, and
+    /// must be greater than zero and at most 15.9375g (`0xFF`), otherwise
+    /// `ErrorKind::Param` is returned.
+    ///
+    /// Activity seen within `refractory_us` microseconds of the last counted
+    /// bump is treated as bounce from the same event, and isn't counted by
+    /// [`Adxl343::poll_bumps`].
+    ///
+    /// The activity interrupt is enabled, and `INT_SOURCE` is read to discard
+    /// any stale events (which also clears other latched interrupts).
+    pub fn configure_bump_counter(
+        &mut self,
+        threshold_g: f32,
+        refractory_us: u64,
+    ) -> Result<(), Error<E>> {
+        if !(threshold_g > 0.0 && threshold_g <= 15.9375) {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let threshold = (threshold_g / 0.0625 + 0.5) as u8;
+        self.write_register(Register::THRESH_ACT, threshold.max(1))?;
+
+        // AC-coupled activity detection on the Z axis, preserving the
+        // inactivity detection settings
+        let act_inact_ctl =
+            ActInactCtl::from_bits_truncate(self.read_register(Register::ACT_INACT_CTL)?)
+                & ActInactCtl::inactivity();
+        self.write_register(
+            Register::ACT_INACT_CTL,
+            (act_inact_ctl | ActInactCtl::ACT_AC | ActInactCtl::ACT_Z).bits(),
+        )?;
+
+        let int_enable = self.read_register(Register::INT_ENABLE)?;
+        self.write_register(
+            Register::INT_ENABLE,
+            int_enable | Interrupts::ACTIVITY.bits(),
+        )?;
+
+        self.read_register(Register::INT_SOURCE)?;
+        self.bump_counter = BumpCounter {
+            refractory_us,
+            last_bump_us: None,
+        };
+        Ok(())
+    }
+
+    /// Poll for activity and return the number of bumps counted since the
+    /// last call (0 or 1), where `now_us` is the current time in microseconds
+    /// from any monotonic clock.
+    ///
+    /// Must be called regularly: the activity interrupt is latched in
+    /// `INT_SOURCE` until read, so at most one bump is registered per poll.
+    /// Activity seen within the refractory period set by
+    /// [`Adxl343::configure_bump_counter`] of the last counted bump is treated
+    /// as bounce from the same event and not counted, however often this is
+    /// called. Bounce doesn't extend the refractory period, so continuous
+    /// vibration is counted once per period.
+    ///
+    /// Reading `INT_SOURCE` clears all latched tap, activity, inactivity,
+    /// and free-fall interrupts.
+    pub fn poll_bumps(&mut self, now_us: u64) -> Result<u32, Error<E>> {
+        let source = Interrupts::from_bits_truncate(self.read_register(Register::INT_SOURCE)?);
+        let counter = &mut self.bump_counter;
+
+        if !source.contains(Interrupts::ACTIVITY) {
+            return Ok(0);
+        }
+
+        if let Some(last) = counter.last_bump_us {
+            if now_us.saturating_sub(last) < counter.refractory_us {
+                return Ok(0);
+            }
+        }
+
+        counter.last_bump_us = Some(now_us);
+        Ok(1)
+    }
+}
@@ -0,0 +1,132 @@
+//! Offset calibration via `OFSX`, `OFSY`, and `OFSZ`
+
+use crate::{register::Register, Adxl343};
+use accelerometer::Error;
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Scale factor of the offset registers in g/LSB
+#[cfg(feature = "i16x3")]
+const OFFSET_G_PER_LSB: f32 = 0.0156;
+
+/// Number of samples averaged when calibrating
+#[cfg(feature = "i16x3")]
+const CALIBRATION_SAMPLES: u8 = 32;
+
+/// Accelerometer axis
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Axis {
+    /// X axis
+    X,
+
+    /// Y axis
+    Y,
+
+    /// Z axis
+    Z,
+}
+
+/// Direction along an axis
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Sign {
+    /// Pointing in the positive direction of the axis
+    Positive,
+
+    /// Pointing in the negative direction of the axis
+    Negative,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Set the X, Y, and Z axis offsets (`OFSX`, `OFSY`, `OFSZ`).
+    ///
+    /// Offsets are twos complement with a scale factor of 15.6 mg/LSB, giving
+    /// each register a range of about ±2g (0x7F = 2g). They are automatically
+    /// added to the acceleration data before it is stored in the output data
+    /// registers.
+    pub fn set_offsets(&mut self, x: i8, y: i8, z: i8) -> Result<(), Error<E>> {
+        self.write_register(Register::OFSX, x as u8)?;
+        self.write_register(Register::OFSY, y as u8)?;
+        self.write_register(Register::OFSZ, z as u8)
+    }
+
+    /// Get the X, Y, and Z axis offsets (15.6 mg/LSB)
+    pub fn offsets(&mut self) -> Result<[i8; 3], Error<E>> {
+        Ok([
+            self.read_register(Register::OFSX)? as i8,
+            self.read_register(Register::OFSY)? as i8,
+            self.read_register(Register::OFSZ)? as i8,
+        ])
+    }
+
+    /// Calibrate the offsets so that a board lying flat (Z axis up) reads
+    /// 0g on X and Y and +1g on Z.
+    ///
+    /// Equivalent to `calibrate_1g(Axis::Z, Sign::Positive, delay)`.
+    #[cfg(feature = "i16x3")]
+    pub fn calibrate_offsets<D>(&mut self, delay: &mut D) -> Result<[i8; 3], Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.calibrate_1g(Axis::Z, Sign::Positive, delay)
+    }
+
+    /// Calibrate the offsets against gravity, with the board stationary and
+    /// the given axis pointing straight up (`Sign::Positive`) or down
+    /// (`Sign::Negative`), so that it reads ±1g on that axis and 0g on the
+    /// others.
+    ///
+    /// Clears the existing offsets, averages several samples (see
+    /// [`Adxl343::accel_norm_averaged`]), then writes offsets which cancel the
+    /// measured error and returns them. The board must be stationary for
+    /// the duration.
+    #[cfg(feature = "i16x3")]
+    pub fn calibrate_1g<D>(
+        &mut self,
+        up_axis: Axis,
+        sign: Sign,
+        delay: &mut D,
+    ) -> Result<[i8; 3], Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.set_offsets(0, 0, 0)?;
+        let average = self.accel_norm_averaged(CALIBRATION_SAMPLES, delay)?;
+
+        let gravity = match sign {
+            Sign::Positive => 1.0,
+            Sign::Negative => -1.0,
+        };
+
+        let expected = |axis: Axis| if axis == up_axis { gravity } else { 0.0 };
+
+        let offsets = [
+            g_to_offset(expected(Axis::X) - average.x),
+            g_to_offset(expected(Axis::Y) - average.y),
+            g_to_offset(expected(Axis::Z) - average.z),
+        ];
+
+        self.set_offsets(offsets[0], offsets[1], offsets[2])?;
+        Ok(offsets)
+    }
+}
+
+/// Convert a correction in g into offset register units, rounding to the
+/// nearest LSB and saturating at the register's limits
+#[cfg(feature = "i16x3")]
+pub(crate) fn g_to_offset(g: f32) -> i8 {
+    let lsb = g / OFFSET_G_PER_LSB;
+    let lsb = if lsb < 0.0 { lsb - 0.5 } else { lsb + 0.5 };
+
+    if lsb >= f32::from(i8::MAX) {
+        i8::MAX
+    } else if lsb <= f32::from(i8::MIN) {
+        i8::MIN
+    } else {
+        lsb as i8
+    }
+}
@@ -0,0 +1,75 @@
+//! Driver-specific error type
+
+use accelerometer::{Error, ErrorKind};
+use core::fmt::{self, Debug, Display};
+
+/// Flattened ADXL343 error type.
+///
+/// Driver methods return [`accelerometer::Error`], which wraps the bus error
+/// along with an [`ErrorKind`]. This type is easier to match on, and can be
+/// obtained from any driver error with `From`/`Into`:
+///
+/// ```ignore
+/// let reading = adxl343.accel_norm().map_err(Adxl343Error::from)?;
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Adxl343Error<E> {
+    /// No ADXL343 (or compatible) device responded with the expected ID
+    DeviceNotFound,
+
+    /// The operation isn't possible in the device's current mode
+    WrongMode,
+
+    /// An invalid parameter was supplied
+    InvalidParam,
+
+    /// Error from the underlying I2C bus
+    Bus(E),
+
+    /// Acceleration was read as a vector type which doesn't match the
+    /// `JUSTIFY` bit of the data format: `I16x3` readings require
+    /// right-justified data (`JUSTIFY` clear), and `U16x3` readings require
+    /// left-justified data (`JUSTIFY` set).
+    ///
+    /// Returned by [`crate::Adxl343::accel_raw_with_endian`] and
+    /// [`crate::Adxl343::accel_both`]. The `RawAccelerometer::accel_raw`
+    /// impls report it as `ErrorKind::Mode`, which converts to
+    /// [`Adxl343Error::WrongMode`].
+    JustifyMismatch,
+
+    /// Any other error
+    Other,
+}
+
+impl<E: Debug> From<Error<E>> for Adxl343Error<E> {
+    fn from(error: Error<E>) -> Adxl343Error<E> {
+        match error.kind() {
+            // `into_cause` panics if there is no cause
+            ErrorKind::Bus if error.cause().is_some() => Adxl343Error::Bus(error.into_cause()),
+            ErrorKind::Bus => Adxl343Error::Other,
+            ErrorKind::Device => Adxl343Error::DeviceNotFound,
+            ErrorKind::Mode => Adxl343Error::WrongMode,
+            ErrorKind::Param => Adxl343Error::InvalidParam,
+        }
+    }
+}
+
+impl<E: Display> Display for Adxl343Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Adxl343Error::DeviceNotFound => f.write_str("ADXL343 device not found"),
+            Adxl343Error::WrongMode => f.write_str("operation not possible in current mode"),
+            Adxl343Error::InvalidParam => f.write_str("invalid parameter"),
+            Adxl343Error::Bus(e) => write!(f, "bus error: {}", e),
+            Adxl343Error::JustifyMismatch => {
+                f.write_str("JUSTIFY bit doesn't match the requested vector type")
+            }
+            Adxl343Error::Other => f.write_str("other error"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug + Display> std::error::Error for Adxl343Error<E> {}
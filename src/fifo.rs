@@ -0,0 +1,312 @@
+//! FIFO support
+
+#[cfg(feature = "i16x3")]
+use crate::{
+    rate::{rate_code_period_us, RATE_MASK},
+    Endian,
+};
+use crate::{
+    register::{Interrupts, Register},
+    Adxl343, IntPin,
+};
+#[cfg(feature = "i16x3")]
+use accelerometer::vector::I16x3;
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// `FIFO_STATUS` FIFO_TRIG bit: a trigger event has occurred
+const FIFO_TRIG: u8 = 0b1000_0000;
+
+/// Mask for the entry count bits of `FIFO_STATUS`
+const FIFO_ENTRIES_MASK: u8 = 0b0011_1111;
+
+/// `FIFO_CTL` Trigger bit: links trigger mode to the INT2 pin (vs INT1)
+const FIFO_TRIGGER_INT2: u8 = 0b0010_0000;
+
+/// Mask for the samples (watermark) bits of `FIFO_CTL`
+const FIFO_SAMPLES_MASK: u8 = 0b0001_1111;
+
+/// FIFO mode, set by the `FIFO_MODE` bits of `Register::FIFO_CTL`
+/// (data sheet p.25)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[repr(u8)]
+pub enum FifoMode {
+    /// FIFO is bypassed (default)
+    #[default]
+    Bypass = 0b0000_0000,
+
+    /// FIFO collects up to 32 values and then stops collecting data
+    Fifo = 0b0100_0000,
+
+    /// FIFO holds the last 32 data values, discarding the oldest when full
+    Stream = 0b1000_0000,
+
+    /// FIFO holds the last data samples before the trigger event and then
+    /// continues to collect data until full
+    Trigger = 0b1100_0000,
+}
+
+impl FifoMode {
+    /// Get the `FIFO_MODE` bits for this mode
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Get the FIFO mode from the contents of `FIFO_CTL`
+    pub fn from_bits(fifo_ctl: u8) -> FifoMode {
+        match fifo_ctl & 0b1100_0000 {
+            0b0000_0000 => FifoMode::Bypass,
+            0b0100_0000 => FifoMode::Fifo,
+            0b1000_0000 => FifoMode::Stream,
+            _ => FifoMode::Trigger,
+        }
+    }
+}
+
+/// FIFO configuration, applied with `Adxl343::configure_fifo`
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FifoConfig {
+    /// FIFO mode
+    pub mode: FifoMode,
+
+    /// Interrupt pin whose trigger event (e.g. activity or tap, as routed by
+    /// `INT_MAP`) stops trigger mode collecting pre-trigger samples
+    /// (`Trigger` bit).
+    ///
+    /// Only meaningful in trigger mode, and ignored by the device in other
+    /// modes.
+    pub trigger_pin: IntPin,
+
+    /// Number of samples (0-31) at which the watermark interrupt triggers in
+    /// FIFO and stream modes, or the number of samples retained from before
+    /// the trigger event in trigger mode
+    pub watermark: u8,
+}
+
+impl FifoConfig {
+    /// Decode a `FIFO_CTL` register value
+    fn from_bits(fifo_ctl: u8) -> FifoConfig {
+        FifoConfig {
+            mode: FifoMode::from_bits(fifo_ctl),
+            trigger_pin: if fifo_ctl & FIFO_TRIGGER_INT2 != 0 {
+                IntPin::Int2
+            } else {
+                IntPin::Int1
+            },
+            watermark: fifo_ctl & FIFO_SAMPLES_MASK,
+        }
+    }
+}
+
+/// Decoded contents of the `FIFO_STATUS` register, returned by
+/// `Adxl343::fifo_status`
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FifoStatus {
+    /// Number of samples available to read (`Entries` bits).
+    ///
+    /// This includes the sample held in the output data registers, so
+    /// although the FIFO holds 32 samples the count can reach 33.
+    pub entries: u8,
+
+    /// A trigger event has occurred in trigger mode (`FIFO_TRIG` bit)
+    pub triggered: bool,
+}
+
+impl From<u8> for FifoStatus {
+    fn from(fifo_status: u8) -> FifoStatus {
+        FifoStatus {
+            entries: fifo_status & FIFO_ENTRIES_MASK,
+            triggered: fifo_status & FIFO_TRIG != 0,
+        }
+    }
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure the FIFO by writing `FIFO_CTL`.
+    ///
+    /// Returns `ErrorKind::Param` if the watermark exceeds 31 samples.
+    pub fn configure_fifo(&mut self, config: FifoConfig) -> Result<(), Error<E>> {
+        if config.watermark > FIFO_SAMPLES_MASK {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let mut fifo_ctl = config.mode.bits() | config.watermark;
+
+        if config.trigger_pin == IntPin::Int2 {
+            fifo_ctl |= FIFO_TRIGGER_INT2;
+        }
+
+        self.write_register(Register::FIFO_CTL, fifo_ctl)
+    }
+
+    /// Start collecting samples in FIFO stream mode, raising the watermark
+    /// interrupt on `int_pin` once `watermark` samples (1-31) are available.
+    ///
+    /// Disables the watermark interrupt while the FIFO and `INT_MAP` are
+    /// configured, then enables it. Other interrupt sources and their pin
+    /// mappings are left unchanged. Drain the samples with
+    /// [`Adxl343::read_fifo`] when the interrupt fires.
+    ///
+    /// Returns `ErrorKind::Param` if the watermark is 0 or exceeds 31.
+    pub fn start_fifo_stream(&mut self, watermark: u8, int_pin: IntPin) -> Result<(), Error<E>> {
+        if watermark == 0 || watermark > FIFO_SAMPLES_MASK {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let enabled = self.interrupts()?;
+        self.set_interrupts(enabled - Interrupts::WATERMARK)?;
+
+        self.route_interrupts(Interrupts::WATERMARK, int_pin)?;
+
+        self.configure_fifo(FifoConfig {
+            mode: FifoMode::Stream,
+            trigger_pin: IntPin::Int1,
+            watermark,
+        })?;
+
+        self.set_interrupts(enabled | Interrupts::WATERMARK)
+    }
+
+    /// Read the FIFO configuration from `FIFO_CTL`
+    pub fn fifo_config(&mut self) -> Result<FifoConfig, Error<E>> {
+        Ok(FifoConfig::from_bits(
+            self.read_register(Register::FIFO_CTL)?,
+        ))
+    }
+
+    /// Read the number of samples available in the FIFO and the trigger
+    /// flag from `FIFO_STATUS`, without reading any samples
+    pub fn fifo_status(&mut self) -> Result<FifoStatus, Error<E>> {
+        Ok(self.read_register(Register::FIFO_STATUS)?.into())
+    }
+
+    /// Drain samples from the FIFO into `buf`, oldest first, and return the
+    /// number of samples read.
+    ///
+    /// Reads `FIFO_STATUS` to find the number of entries, then reads the data
+    /// registers once per entry. Never reads past the available entries, and
+    /// stops early when `buf` is full.
+    #[cfg(feature = "i16x3")]
+    pub fn read_fifo(&mut self, buf: &mut [I16x3]) -> Result<usize, Error<E>> {
+        self.drain_fifo(buf.len(), |index, sample| buf[index] = sample)
+    }
+
+    /// Drain samples from the FIFO into `buf` like [`Adxl343::read_fifo`],
+    /// also reporting whether the FIFO overran since it was last drained.
+    ///
+    /// Returns the number of samples read, and `true` if the `OVERRUN` bit of
+    /// `INT_SOURCE` was set, meaning unread samples were discarded and there
+    /// is a gap before the oldest sample returned. Note that reading
+    /// `INT_SOURCE` clears any latched tap, activity, inactivity, and
+    /// free-fall interrupts.
+    #[cfg(feature = "i16x3")]
+    pub fn read_fifo_checked(&mut self, buf: &mut [I16x3]) -> Result<(usize, bool), Error<E>> {
+        let source = Interrupts::from_bits_truncate(self.read_register(Register::INT_SOURCE)?);
+        let count = self.read_fifo(buf)?;
+        Ok((count, source.contains(Interrupts::OVERRUN)))
+    }
+
+    /// Wait until the FIFO reaches its watermark, then drain the watermark's
+    /// worth of samples into `buf` (or fewer if `buf` is smaller), returning
+    /// the number of samples read.
+    ///
+    /// Polls the `WATERMARK` bit of `INT_SOURCE` every millisecond, which
+    /// clears any latched tap, activity, inactivity, and free-fall interrupts.
+    ///
+    /// Returns `ErrorKind::Mode` if the FIFO is bypassed, if it overruns
+    /// while waiting (samples were lost), or if the watermark isn't reached
+    /// within twice the time it should take at the current data rate.
+    /// Returns `ErrorKind::Param` if the watermark is zero.
+    #[cfg(feature = "i16x3")]
+    pub fn read_watermark<D>(&mut self, buf: &mut [I16x3], delay: &mut D) -> Result<usize, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let config = self.fifo_config()?;
+
+        if config.mode == FifoMode::Bypass {
+            return Err(Error::new(ErrorKind::Mode));
+        }
+
+        if config.watermark == 0 {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let rate_code = self.read_register(Register::BW_RATE)? & RATE_MASK;
+        let timeout_ms = 2 * rate_code_period_us(rate_code, u64::from(config.watermark)) / 1000 + 1;
+        let mut waited_ms = 0;
+
+        loop {
+            let source = Interrupts::from_bits_truncate(self.read_register(Register::INT_SOURCE)?);
+
+            if source.contains(Interrupts::OVERRUN) {
+                return Err(Error::new(ErrorKind::Mode));
+            }
+
+            if source.contains(Interrupts::WATERMARK) {
+                break;
+            }
+
+            if waited_ms >= timeout_ms {
+                return Err(Error::new(ErrorKind::Mode));
+            }
+
+            delay.delay_ms(1);
+            waited_ms += 1;
+        }
+
+        let max = buf.len().min(usize::from(config.watermark));
+        self.drain_fifo(max, |index, sample| buf[index] = sample)
+    }
+
+    /// Drain samples from the FIFO into `out`, tagging each with a timestamp
+    /// in microseconds, and return the number of samples read.
+    ///
+    /// Samples in the FIFO were captured at the output data rate, so the
+    /// oldest sample is stamped `base_time_us` and each following sample is
+    /// one sample period (as currently configured in `BW_RATE`) later.
+    ///
+    /// Reads no more than the number of entries reported by `FIFO_STATUS`,
+    /// and stops early when `out` is full.
+    #[cfg(feature = "i16x3")]
+    pub fn read_fifo_timed(
+        &mut self,
+        base_time_us: u64,
+        out: &mut [(u64, I16x3)],
+    ) -> Result<usize, Error<E>> {
+        let rate_code = self.read_register(Register::BW_RATE)? & RATE_MASK;
+
+        self.drain_fifo(out.len(), |index, sample| {
+            let timestamp = base_time_us + rate_code_period_us(rate_code, index as u64);
+            out[index] = (timestamp, sample);
+        })
+    }
+
+    /// Read up to `max` samples from the FIFO, passing each to `f` along with
+    /// its index, and return the number of samples read
+    #[cfg(feature = "i16x3")]
+    fn drain_fifo<F>(&mut self, max: usize, mut f: F) -> Result<usize, Error<E>>
+    where
+        F: FnMut(usize, I16x3),
+    {
+        self.data_format.check_justify(false)?;
+
+        let entries = self.fifo_status()?.entries;
+        let count = max.min(usize::from(entries));
+
+        for index in 0..count {
+            // The data sheet requires 5 µs between the end of one FIFO read and
+            // the start of the next, which the I2C transaction overhead
+            // already exceeds.
+            f(index, self.read_data_i16x3(Endian::Little)?);
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,81 @@
+//! Free-fall detection
+
+use crate::{
+    register::{mg_to_thresh, thresh_to_mg, Register},
+    Adxl343,
+};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Recommended range for the free-fall threshold in mg (data sheet p.22)
+const THRESHOLD_RANGE_MG: (u16, u16) = (300, 600);
+
+/// Recommended range for the free-fall time in ms (data sheet p.22)
+const TIME_RANGE_MS: (u16, u16) = (100, 350);
+
+/// Free-fall detection configuration, as read back from the device by
+/// `Adxl343::free_fall_config`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FreeFallConfig {
+    /// Threshold below which all axes must fall, in mg (`THRESH_FF`)
+    pub threshold_mg: u16,
+
+    /// Minimum time all axes must stay below the threshold, in ms (`TIME_FF`)
+    pub time_ms: u16,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure free-fall detection.
+    ///
+    /// - `threshold_mg`: programmed into `THRESH_FF` at 62.5 mg/LSB
+    /// - `time_ms`: programmed into `TIME_FF` at 5 ms/LSB
+    ///
+    /// Values are rounded to the nearest LSB. The data sheet recommends
+    /// thresholds between 300 mg and 600 mg and times between 100 ms and
+    /// 350 ms: values outside these ranges return `ErrorKind::Param`.
+    pub fn configure_free_fall(&mut self, threshold_mg: u16, time_ms: u16) -> Result<(), Error<E>> {
+        if threshold_mg < THRESHOLD_RANGE_MG.0
+            || threshold_mg > THRESHOLD_RANGE_MG.1
+            || time_ms < TIME_RANGE_MS.0
+            || time_ms > TIME_RANGE_MS.1
+        {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let threshold = mg_to_thresh(threshold_mg).ok_or_else(|| Error::new(ErrorKind::Param))?;
+
+        // 5 ms/LSB, rounded
+        let time = (time_ms + 2) / 5;
+
+        self.write_register(Register::THRESH_FF, threshold)?;
+        self.write_register(Register::TIME_FF, time as u8)
+    }
+
+    /// Read the free-fall detection configuration from `THRESH_FF` and `TIME_FF`
+    pub fn free_fall_config(&mut self) -> Result<FreeFallConfig, Error<E>> {
+        let threshold = self.read_register(Register::THRESH_FF)?;
+        let time = u16::from(self.read_register(Register::TIME_FF)?);
+
+        Ok(FreeFallConfig {
+            threshold_mg: thresh_to_mg(threshold),
+            time_ms: time * 5,
+        })
+    }
+
+    /// Set the free-fall threshold (`THRESH_FF`) in mg.
+    ///
+    /// Rounded to the nearest 62.5 mg LSB. Thresholds above 15.9375g don't
+    /// fit in the register and return `ErrorKind::Param`. Unlike
+    /// [`Adxl343::configure_free_fall`], values outside the recommended 300 mg
+    /// to 600 mg are accepted.
+    pub fn set_free_fall_threshold_mg(&mut self, threshold_mg: u16) -> Result<(), Error<E>> {
+        let threshold = mg_to_thresh(threshold_mg).ok_or_else(|| Error::new(ErrorKind::Param))?;
+        self.write_register(Register::THRESH_FF, threshold)
+    }
+}
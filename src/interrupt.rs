@@ -0,0 +1,234 @@
+//! Interrupt configuration
+
+use crate::{
+    register::{DataFormatFlags, Interrupts, Register},
+    Adxl343,
+};
+use accelerometer::Error;
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Interrupt output pin
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IntPin {
+    /// INT1 pin (default)
+    #[default]
+    Int1,
+
+    /// INT2 pin
+    Int2,
+}
+
+/// Interrupt configuration: which sources are enabled, which pin they are
+/// routed to, and the polarity of the interrupt pins.
+///
+/// Applied with `Adxl343::configure_interrupts`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct InterruptConfig {
+    /// Interrupt sources which generate an interrupt (`INT_ENABLE`)
+    pub enabled: Interrupts,
+
+    /// Interrupt sources routed to the INT2 pin (`INT_MAP`).
+    /// Sources not included here are routed to INT1.
+    pub int2_routed: Interrupts,
+
+    /// Drive the interrupt pins active low (`DATA_FORMAT` `INT_INVERT` bit)
+    pub active_low: bool,
+}
+
+/// Interrupt sources to enable, each with the pin it is routed to, built
+/// with [`InterruptPlan::route`] and applied with
+/// `Adxl343::apply_interrupt_plan`:
+///
+/// ```ignore
+/// let plan = InterruptPlan::new()
+///     .route(Interrupts::SINGLE_TAP, IntPin::Int1)
+///     .route(Interrupts::FREE_FALL, IntPin::Int2);
+///
+/// adxl343.apply_interrupt_plan(plan)?;
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct InterruptPlan {
+    /// Interrupt sources to enable (`INT_ENABLE`)
+    enabled: Interrupts,
+
+    /// Interrupt sources routed to the INT2 pin (`INT_MAP`)
+    int2_routed: Interrupts,
+}
+
+impl InterruptPlan {
+    /// Create an empty plan, with all interrupt sources disabled
+    pub fn new() -> InterruptPlan {
+        InterruptPlan::default()
+    }
+
+    /// Enable the given interrupt sources and route them to `pin`,
+    /// replacing any earlier routing of the same sources
+    pub fn route(mut self, sources: Interrupts, pin: IntPin) -> InterruptPlan {
+        self.enabled |= sources;
+        self.int2_routed.set(sources, pin == IntPin::Int2);
+        self
+    }
+
+    /// Get the interrupt sources enabled by this plan
+    pub fn enabled(&self) -> Interrupts {
+        self.enabled
+    }
+
+    /// Get the pin an interrupt source is routed to, or `None` if it isn't
+    /// enabled by this plan
+    pub fn pin(&self, source: Interrupts) -> Option<IntPin> {
+        if !self.enabled.contains(source) {
+            None
+        } else if self.int2_routed.contains(source) {
+            Some(IntPin::Int2)
+        } else {
+            Some(IntPin::Int1)
+        }
+    }
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure interrupt enables, pin mapping, and polarity in one call.
+    ///
+    /// Interrupt outputs are disabled while `INT_MAP` and the `INT_INVERT`
+    /// bit of `DATA_FORMAT` are written, and `INT_ENABLE` is written last.
+    /// From the ADXL343 data sheet (p.24):
+    ///
+    /// "It is recommended that interrupts be configured before enabling
+    /// their outputs."
+    pub fn configure_interrupts(&mut self, config: InterruptConfig) -> Result<(), Error<E>> {
+        self.write_register(Register::INT_ENABLE, 0)?;
+        self.write_register(Register::INT_MAP, config.int2_routed.bits())?;
+
+        let mut data_format = self.data_format;
+        data_format.set(DataFormatFlags::INT_INVERT, config.active_low);
+        self.data_format(data_format)?;
+
+        self.write_register(Register::INT_ENABLE, config.enabled.bits())
+    }
+
+    /// Enable and route interrupt sources according to `plan`, writing
+    /// `INT_ENABLE` and `INT_MAP`.
+    ///
+    /// Sources not in the plan are disabled. As with
+    /// [`Adxl343::configure_interrupts`], interrupt outputs are disabled while
+    /// `INT_MAP` is written and `INT_ENABLE` is written last, so no interrupt
+    /// is raised on the wrong pin. The polarity of the pins is unchanged.
+    pub fn apply_interrupt_plan(&mut self, plan: InterruptPlan) -> Result<(), Error<E>> {
+        self.write_register(Register::INT_ENABLE, 0)?;
+        self.write_register(Register::INT_MAP, plan.int2_routed.bits())?;
+        self.write_register(Register::INT_ENABLE, plan.enabled.bits())
+    }
+
+    /// Reconstruct the interrupt plan applied to the device by reading
+    /// `INT_ENABLE` and `INT_MAP`.
+    ///
+    /// Routing of disabled sources isn't included in the plan.
+    pub fn interrupt_plan(&mut self) -> Result<InterruptPlan, Error<E>> {
+        let enabled = self.interrupts()?;
+        let int2_routed = self.interrupt_map()?;
+
+        Ok(InterruptPlan {
+            enabled,
+            int2_routed: int2_routed & enabled,
+        })
+    }
+
+    /// Set the polarity of the interrupt pins via the `INT_INVERT` bit of
+    /// `DATA_FORMAT`, leaving the other data format bits unchanged.
+    ///
+    /// Interrupts are active high by default. Set `active_low` when the
+    /// interrupt line is pulled up, for example.
+    pub fn set_interrupt_polarity(&mut self, active_low: bool) -> Result<(), Error<E>> {
+        let mut data_format = self.data_format;
+        data_format.set(DataFormatFlags::INT_INVERT, active_low);
+        self.data_format(data_format)
+    }
+
+    /// Enable the given interrupt sources, writing `INT_ENABLE`.
+    /// Sources not included are disabled.
+    pub fn set_interrupts(&mut self, interrupts: Interrupts) -> Result<(), Error<E>> {
+        self.write_register(Register::INT_ENABLE, interrupts.bits())
+    }
+
+    /// Get the enabled interrupt sources by reading `INT_ENABLE`
+    pub fn interrupts(&mut self) -> Result<Interrupts, Error<E>> {
+        let int_enable = self.read_register(Register::INT_ENABLE)?;
+        Ok(Interrupts::from_bits_truncate(int_enable))
+    }
+
+    /// Route interrupt sources to the interrupt pins, writing `INT_MAP`.
+    ///
+    /// Sources included in `to_int2` are sent to the INT2 pin, and all other
+    /// sources to the INT1 pin.
+    pub fn map_interrupts(&mut self, to_int2: Interrupts) -> Result<(), Error<E>> {
+        self.write_register(Register::INT_MAP, to_int2.bits())
+    }
+
+    /// Route the given interrupt sources to `pin` with a read-modify-write of
+    /// `INT_MAP`, leaving the mapping of other sources unchanged
+    pub(crate) fn route_interrupts(
+        &mut self,
+        sources: Interrupts,
+        pin: IntPin,
+    ) -> Result<(), Error<E>> {
+        let mut int2_routed = self.interrupt_map()?;
+        int2_routed.set(sources, pin == IntPin::Int2);
+        self.map_interrupts(int2_routed)
+    }
+
+    /// Get the interrupt sources routed to the INT2 pin by reading `INT_MAP`
+    pub fn interrupt_map(&mut self) -> Result<Interrupts, Error<E>> {
+        let int_map = self.read_register(Register::INT_MAP)?;
+        Ok(Interrupts::from_bits_truncate(int_map))
+    }
+
+    /// Get the interrupt sources which have triggered by reading `INT_SOURCE`.
+    ///
+    /// Reading `INT_SOURCE` clears the latched `SINGLE_TAP`, `DOUBLE_TAP`,
+    /// `ACTIVITY`, `INACTIVITY`, and `FREE_FALL` bits. `DATA_READY`,
+    /// `WATERMARK`, and `OVERRUN` are not cleared by this read: they reflect
+    /// the state of the data registers and FIFO, and are only cleared by
+    /// reading data from `DATAX`, `DATAY`, and `DATAZ`.
+    pub fn interrupt_source(&mut self) -> Result<Interrupts, Error<E>> {
+        Ok(Interrupts::from_bits_truncate(self.interrupt_source_raw()?))
+    }
+
+    /// Read the raw `INT_SOURCE` byte, with the same side effects as
+    /// [`Adxl343::interrupt_source`].
+    ///
+    /// Unlike the decoded flags, this includes any reserved bits, which can
+    /// be useful for logging. Decode it with `Interrupts::from_bits_truncate`.
+    pub fn interrupt_source_raw(&mut self) -> Result<u8, Error<E>> {
+        Ok(self.read_register(Register::INT_SOURCE)?)
+    }
+
+    /// Clear all latched interrupt conditions, returning the interrupt
+    /// sources which were set, e.g. to recover an interrupt line left
+    /// asserted after a missed `INT_SOURCE` read.
+    ///
+    /// Reads `INT_SOURCE`, which clears `SINGLE_TAP`, `DOUBLE_TAP`,
+    /// `ACTIVITY`, `INACTIVITY`, and `FREE_FALL`. Then reads the data
+    /// registers once for every sample held in the FIFO (or once if the FIFO
+    /// is bypassed), which clears `DATA_READY`, `WATERMARK`, and `OVERRUN`.
+    /// The samples read are discarded.
+    ///
+    /// New interrupts may be raised as soon as further samples are taken.
+    pub fn clear_interrupts(&mut self) -> Result<Interrupts, Error<E>> {
+        let source = self.interrupt_source()?;
+        let entries = self.fifo_status()?.entries.max(1);
+
+        for _ in 0..entries {
+            self.read_data_registers()?;
+        }
+
+        Ok(source)
+    }
+}
@@ -2,7 +2,13 @@
 //! [embedded-hal] and implements the [`Accelerometer` trait][trait]
 //! from the `accelerometer` crate.
 //!
+//! The driver accepts any [`embedded_hal::i2c::I2c`] implementation. To share
+//! the bus with other devices, pass a `&mut` reference to the bus, or one of
+//! the shared bus wrappers from [embedded-hal-bus] such as `RefCellDevice`
+//! or `CriticalSectionDevice`.
+//!
 //! [embedded-hal]: https://docs.rs/embedded-hal
+//! [embedded-hal-bus]: https://docs.rs/embedded-hal-bus
 //! [trait]: https://docs.rs/accelerometer/latest/accelerometer/trait.Accelerometer.html
 
 #![no_std]
@@ -10,13 +16,60 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms, unused_qualifications)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+mod activity;
+#[cfg(feature = "async")]
+mod asynch;
+mod builder;
+mod bump;
+mod calibration;
+mod error;
+mod fifo;
+mod free_fall;
+mod interrupt;
+#[cfg(feature = "i16x3")]
+mod orientation;
+mod power;
+mod rate;
 mod register;
-
-pub use crate::register::{DataFormatFlags, DataFormatRange};
+#[cfg(feature = "i16x3")]
+mod sampling;
+#[cfg(feature = "i16x3")]
+mod self_test;
+mod snapshot;
+mod tap;
+
+#[cfg(feature = "async")]
+pub use crate::asynch::Adxl343Async;
+pub use crate::{
+    activity::ActivityConfig,
+    builder::Adxl343Builder,
+    calibration::{Axis, Sign},
+    error::Adxl343Error,
+    fifo::{FifoConfig, FifoMode, FifoStatus},
+    free_fall::FreeFallConfig,
+    interrupt::{IntPin, InterruptConfig, InterruptPlan},
+    power::{AutoSleepConfig, WakeupRate},
+    rate::DataRate,
+    register::{
+        ActInactCtl, DataFormatConfig, DataFormatFlags, DataFormatRange, Interrupts, Register,
+        ScaleFactor, ScaleUnit, TapAxes,
+    },
+    snapshot::{ConfigSnapshot, DeviceStatus},
+    tap::{DoubleTapConfig, TapConfig, TapStatus},
+};
+#[cfg(feature = "i16x3")]
+pub use crate::{orientation::Orientation, self_test::SelfTestResult};
 pub use accelerometer;
 use embedded_hal as hal;
 
-use crate::register::Register;
+use crate::{
+    bump::BumpCounter,
+    power::MEASURE,
+    rate::{rate_code_period_us, LOW_POWER, RATE_MASK},
+};
 #[cfg(feature = "u16x3")]
 use accelerometer::vector::U16x3;
 #[cfg(feature = "i16x3")]
@@ -26,32 +79,143 @@ use accelerometer::{
 };
 use accelerometer::{Error, ErrorKind, RawAccelerometer};
 use core::fmt::Debug;
-use hal::blocking::i2c::{Write, WriteRead};
+use hal::delay::DelayNs;
+use hal::i2c::I2c;
 
 /// ADXL343 I2C address.
-/// Assumes ALT address pin low
+/// Assumes ALT address pin low (`addr-0x53` feature, enabled by default)
+#[cfg(feature = "addr-0x53")]
 pub const ADDRESS: u8 = 0x53;
 
+/// ADXL343 I2C address.
+/// Assumes ALT address pin high (`addr-0x1d` feature, which requires
+/// disabling the default features)
+#[cfg(feature = "addr-0x1d")]
+pub const ADDRESS: u8 = 0x1D;
+
+#[cfg(all(feature = "addr-0x53", feature = "addr-0x1d"))]
+compile_error!(
+    "the `addr-0x53` and `addr-0x1d` features are mutually exclusive \
+     (disable default features to select `addr-0x1d`)"
+);
+
+#[cfg(not(any(feature = "addr-0x53", feature = "addr-0x1d")))]
+compile_error!("one of the `addr-0x53` or `addr-0x1d` features must be enabled");
+
 /// ADXL343 device ID
 pub const DEVICE_ID: u8 = 0xE5;
 
+/// Turn-on and wake-up time in microseconds (data sheet p.4, Table 1),
+/// after which a further sample period is needed for valid data
+const TURN_ON_TIME_US: u64 = 1100;
+
+/// Standard acceleration due to gravity, in m/s²
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// Maximum time to wait for `DATA_READY` before giving up, in milliseconds.
+/// Longer than one sample period at the slowest (0.1 Hz) data rate.
+#[cfg(feature = "i16x3")]
+const DATA_READY_TIMEOUT_MS: u16 = 11_000;
+
+/// Register writes which complete the initial configuration applied by
+/// both drivers, after the data format is written and `BW_RATE` is read:
+/// interrupts disabled, single tap detection on all axes (see
+/// [`TapConfig::default`]), then measurement enabled
+pub(crate) fn init_registers() -> [(Register, u8); 7] {
+    let [thresh_tap, dur, latent, window, tap_axes] = TapConfig::default().registers();
+
+    [
+        (Register::INT_ENABLE, 0),
+        thresh_tap,
+        dur,
+        latent,
+        window,
+        tap_axes,
+        (Register::POWER_CTL, MEASURE),
+    ]
+}
+
+/// Device identification, as classified from the `DEVID` register
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeviceIdentity {
+    /// `DEVID` reads [`DEVICE_ID`] (0xE5). This is shared by the ADXL343
+    /// and register-compatible parts such as the ADXL345.
+    Adxl343Compatible,
+
+    /// `DEVID` reads an unrecognized value
+    Unknown(u8),
+}
+
+impl From<u8> for DeviceIdentity {
+    fn from(device_id: u8) -> DeviceIdentity {
+        if device_id == DEVICE_ID {
+            DeviceIdentity::Adxl343Compatible
+        } else {
+            DeviceIdentity::Unknown(device_id)
+        }
+    }
+}
+
+/// Byte order used to interpret the pair of data registers for each axis
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Endian {
+    /// Least significant byte first.
+    ///
+    /// This is the order given in the data sheet (p.25): "DATAx0 as the least
+    /// significant byte and DATAx1 as the most significant byte"
+    Little,
+
+    /// Most significant byte first
+    Big,
+}
+
+/// Delay provider which doesn't wait, used by drivers created without one
+/// (see [`Adxl343::new_with_delay`])
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
 /// ADXL343 driver
-pub struct Adxl343<I2C> {
+pub struct Adxl343<I2C, DELAY = NoDelay> {
     /// Underlying I2C device
     i2c: I2C,
 
+    /// Delay used to wait for the device to settle after configuration
+    /// changes
+    delay: DELAY,
+
+    /// I2C address of the device
+    address: u8,
+
     /// Current data format
     data_format: DataFormatFlags,
+
+    /// Current output data rate
+    data_rate: DataRate,
+
+    /// Number of times a failed bus transaction is retried
+    retries: u8,
+
+    /// Bump counter state
+    bump_counter: BumpCounter,
+
+    /// Baseline self-test response used to compute drift
+    #[cfg(feature = "i16x3")]
+    self_test_baseline: Option<F32x3>,
 }
 
-impl<I2C, E> Adxl343<I2C>
+impl<I2C, E> Adxl343<I2C, NoDelay>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
     E: Debug,
 {
     /// Create a new ADXL343 driver from the given I2C peripheral
     ///
-    /// Default tap detection level: 2G, 31.25ms duration, single tap only
+    /// Default tap detection level: 1.25g, 31.25ms duration, single tap only
+    /// (see [`TapConfig::default`])
     pub fn new(i2c: I2C) -> Result<Self, Error<E>> {
         Self::new_with_data_format(i2c, DataFormatFlags::default())
     }
@@ -61,85 +225,634 @@ where
     where
         F: Into<DataFormatFlags>,
     {
-        let mut adxl343 = Adxl343 {
-            i2c,
-            data_format: data_format.into(),
-        };
+        Self::connect(i2c, ADDRESS, data_format.into(), NoDelay)
+    }
 
-        // Ensure we have the correct device ID for the ADLX343
-        if adxl343.get_device_id()? != DEVICE_ID {
-            ErrorKind::Device.err()?;
-        }
+    /// Create a new ADXL343 driver for a device at the given I2C address,
+    /// e.g. `0x1D` for a device with its ALT ADDRESS pin tied high.
+    ///
+    /// This overrides the address selected by the `addr-0x53`/`addr-0x1d`
+    /// features, allowing it to be chosen at runtime.
+    pub fn new_with_address(i2c: I2C, address: u8) -> Result<Self, Error<E>> {
+        Self::connect(i2c, address, DataFormatFlags::default(), NoDelay)
+    }
 
-        // Configure the data format
+    /// Create a new ADXL343 driver for plain acceleration readings.
+    ///
+    /// Only checks the device ID, writes the default data format, and enables
+    /// measurement. Unlike [`Adxl343::new`], tap detection and interrupt
+    /// registers are left at their current (on power-up, reset) values.
+    pub fn new_raw(i2c: I2C) -> Result<Self, Error<E>> {
+        let mut adxl343 = Self::probe(i2c, ADDRESS, DataFormatFlags::default(), NoDelay)?;
         adxl343.data_format(adxl343.data_format)?;
+        adxl343.data_rate = DataRate::from_code(adxl343.read_register(Register::BW_RATE)?);
+        adxl343.write_register(Register::POWER_CTL, MEASURE)?;
+        Ok(adxl343)
+    }
 
-        // Disable interrupts
-        adxl343.write_register(Register::INT_ENABLE, 0)?;
+    /// Create a new ADXL343 driver configured with the given data format,
+    /// without checking the device ID.
+    ///
+    /// Applies the same initial configuration as
+    /// [`Adxl343::new_with_data_format`]. Use this for register-compatible
+    /// parts with a different ID, or to check the ID separately with
+    /// [`Adxl343::verify_device_id`] (e.g. retrying on a flaky bus).
+    pub fn new_unchecked<F>(i2c: I2C, data_format: F) -> Result<Self, Error<E>>
+    where
+        F: Into<DataFormatFlags>,
+    {
+        let mut adxl343 = Self::unprobed(i2c, ADDRESS, data_format.into(), NoDelay);
+        adxl343.init()?;
+        Ok(adxl343)
+    }
 
-        // 62.5 mg/LSB
-        adxl343.write_register(Register::THRESH_TAP, 20)?;
+    /// Create a new ADXL343 driver configured with the given data format,
+    /// which retries each failed bus transaction up to `retries` times
+    /// before returning the error (see [`Adxl343::set_retries`]).
+    ///
+    /// Retries apply from the first transaction, including the device ID
+    /// check.
+    pub fn new_with_retries<F>(i2c: I2C, data_format: F, retries: u8) -> Result<Self, Error<E>>
+    where
+        F: Into<DataFormatFlags>,
+    {
+        let mut adxl343 = Self::unprobed(i2c, ADDRESS, data_format.into(), NoDelay);
+        adxl343.retries = retries;
 
-        // Tap duration: 625 µs/LSB
-        adxl343.write_register(Register::DUR, 50)?;
+        if !adxl343.verify_device_id()? {
+            ErrorKind::Device.err()?;
+        }
 
-        // Tap latency: 1.25 ms/LSB (0 = no double tap)
-        adxl343.write_register(Register::LATENT, 0)?;
+        adxl343.init()?;
+        Ok(adxl343)
+    }
+}
 
-        // Waiting period: 1.25 ms/LSB (0 = no double tap)
-        adxl343.write_register(Register::WINDOW, 0)?;
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Create a new ADXL343 driver which uses the given delay to wait for the
+    /// device to settle after changing its configuration.
+    ///
+    /// After changing the data rate, range, or resolution, or enabling
+    /// measurement, the driver waits for the turn-on time (1.1 ms) plus one
+    /// sample period (data sheet p.4, Table 1) so the next sample read
+    /// reflects the new configuration. Drivers created with other
+    /// constructors don't wait.
+    ///
+    /// Otherwise initializes the device as [`Adxl343::new`] does.
+    pub fn new_with_delay(i2c: I2C, delay: DELAY) -> Result<Self, Error<E>> {
+        Self::connect(i2c, ADDRESS, DataFormatFlags::default(), delay)
+    }
 
-        // Enable XYZ axis for tap
-        adxl343.write_register(Register::TAP_AXES, 0x7)?;
+    /// Check the device ID and apply the initial configuration
+    fn connect(
+        i2c: I2C,
+        address: u8,
+        data_format: DataFormatFlags,
+        delay: DELAY,
+    ) -> Result<Self, Error<E>> {
+        let mut adxl343 = Self::probe(i2c, address, data_format, delay)?;
+        adxl343.init()?;
+        Ok(adxl343)
+    }
 
-        // Enable measurements
-        adxl343.write_register(Register::POWER_CTL, 0x08)?;
+    /// Create a driver and check the device ID, without writing any registers
+    fn probe(
+        i2c: I2C,
+        address: u8,
+        data_format: DataFormatFlags,
+        delay: DELAY,
+    ) -> Result<Self, Error<E>> {
+        let mut adxl343 = Self::unprobed(i2c, address, data_format, delay);
+
+        // Ensure we have the correct device ID for the ADLX343
+        if !adxl343.verify_device_id()? {
+            ErrorKind::Device.err()?;
+        }
 
         Ok(adxl343)
     }
 
+    /// Create a driver without communicating with the device
+    fn unprobed(i2c: I2C, address: u8, data_format: DataFormatFlags, delay: DELAY) -> Self {
+        Adxl343 {
+            i2c,
+            delay,
+            address,
+            data_format,
+            data_rate: DataRate::default(),
+            retries: 0,
+            bump_counter: BumpCounter::default(),
+            #[cfg(feature = "i16x3")]
+            self_test_baseline: None,
+        }
+    }
+
+    /// Return the device to the state established by the constructor without
+    /// re-checking the device ID, e.g. to recover after a brownout.
+    ///
+    /// Rewrites the cached data format, disables interrupts, restores the
+    /// default tap detection settings, and enables measurement.
+    pub fn reset(&mut self) -> Result<(), Error<E>> {
+        self.init()
+    }
+
+    /// Apply the initial device configuration
+    fn init(&mut self) -> Result<(), Error<E>> {
+        // Configure the data format
+        self.data_format(self.data_format)?;
+
+        // Read the current output data rate
+        let bw_rate = self.read_register(Register::BW_RATE)?;
+        self.data_rate = DataRate::from_code(bw_rate);
+
+        for (register, value) in init_registers() {
+            self.write_register(register, value)?;
+        }
+
+        self.settle();
+
+        Ok(())
+    }
+
     /// Set the device data format
     pub fn data_format<F>(&mut self, data_format: F) -> Result<(), Error<E>>
     where
         F: Into<DataFormatFlags>,
     {
         let f = data_format.into();
-        let input = [Register::DATA_FORMAT.addr(), f.bits()];
-        self.i2c.write(ADDRESS, &input)?;
+        self.bus_write(&f.register_write())?;
+
+        let scaling =
+            DataFormatFlags::FULL_RES | DataFormatFlags::RANGE_HI | DataFormatFlags::RANGE_LO;
+        let rescaled = (self.data_format ^ f).intersects(scaling);
         self.data_format = f;
+
+        if rescaled {
+            self.settle();
+        }
+
+        Ok(())
+    }
+
+    /// Update the data format by applying `f` to the cached flags and
+    /// writing the result, e.g. to toggle a single flag without clobbering
+    /// the others:
+    ///
+    /// ```ignore
+    /// adxl343.modify_data_format(|flags| flags | DataFormatFlags::FULL_RES)?;
+    /// ```
+    pub fn modify_data_format<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(DataFormatFlags) -> DataFormatFlags,
+    {
+        let data_format = f(self.data_format);
+        self.data_format(data_format)
+    }
+
+    /// Temporarily switch to the given g range while running `f`, then
+    /// restore the previous range, e.g. to capture a high-g event at ±16g
+    /// before returning to ±2g for finer resolution:
+    ///
+    /// ```ignore
+    /// let peak = adxl343.with_range(DataFormatRange::PLUSMINUS_16G, |a| a.accel_norm())??;
+    /// ```
+    ///
+    /// The previous range is restored however `f` returns, including when
+    /// it returns an error. Only the range bits are restored, so any other
+    /// data format changes made by `f` are kept. Returns an error without
+    /// running `f` if the range can't be set, and discards the result of
+    /// `f` if the range can't be restored.
+    pub fn with_range<R, F>(&mut self, range: DataFormatRange, f: F) -> Result<R, Error<E>>
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let range_bits = DataFormatFlags::RANGE_HI | DataFormatFlags::RANGE_LO;
+        let previous = self.data_format.range();
+
+        self.modify_data_format(|flags| (flags - range_bits) | range.bits())?;
+        let result = f(self);
+        self.modify_data_format(|flags| (flags - range_bits) | previous.bits())?;
+
+        Ok(result)
+    }
+
+    /// Set the output data rate.
+    ///
+    /// Performs a read-modify-write of `BW_RATE` which preserves the
+    /// `LOW_POWER` bit.
+    pub fn set_data_rate(&mut self, rate: DataRate) -> Result<(), Error<E>> {
+        let bw_rate = self.read_register(Register::BW_RATE)?;
+        self.write_register(Register::BW_RATE, (bw_rate & !RATE_MASK) | rate.code())?;
+        self.data_rate = rate;
+        self.settle();
         Ok(())
     }
 
-    /// Write to the given register
+    /// Enable or disable low power mode via the `LOW_POWER` bit of `BW_RATE`,
+    /// which reduces current consumption at the cost of higher noise.
+    ///
+    /// Performs a read-modify-write which preserves the rate code. Low power
+    /// mode is only available for output data rates from 12.5 Hz to 400 Hz:
+    /// enabling it while the device is configured for any other rate returns
+    /// `ErrorKind::Mode`.
+    pub fn set_low_power(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        let bw_rate = self.read_register(Register::BW_RATE)?;
+
+        if enabled && !DataRate::from_code(bw_rate).supports_low_power() {
+            return Err(Error::new(ErrorKind::Mode));
+        }
+
+        let bw_rate = if enabled {
+            bw_rate | LOW_POWER
+        } else {
+            bw_rate & !LOW_POWER
+        };
+
+        self.write_register(Register::BW_RATE, bw_rate)
+    }
+
+    /// Is low power mode enabled? Reads the `LOW_POWER` bit of `BW_RATE`.
+    pub fn low_power(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register(Register::BW_RATE)? & LOW_POWER != 0)
+    }
+
+    /// Get the output data rate last configured via this driver
+    pub fn data_rate(&self) -> DataRate {
+        self.data_rate
+    }
+
+    /// Get the g range from the cached data format, without reading from the
+    /// device
+    pub fn range(&self) -> DataFormatRange {
+        self.data_format.range()
+    }
+
+    /// Check whether full resolution mode is enabled in the cached data
+    /// format, without reading from the device
+    pub fn full_resolution(&self) -> bool {
+        self.data_format.contains(DataFormatFlags::FULL_RES)
+    }
+
+    /// Get the cached data format, without reading from the device
+    pub fn data_format_flags(&self) -> DataFormatFlags {
+        self.data_format
+    }
+
+    /// Read the `DATA_FORMAT` register from the device without updating the
+    /// cached data format.
+    ///
+    /// Compare the result with [`Adxl343::data_format_flags`] to check that
+    /// a write took effect, or to detect the register drifting from the
+    /// cached copy (see [`Adxl343::refresh_data_format`] to resynchronize).
+    pub fn read_data_format(&mut self) -> Result<DataFormatFlags, Error<E>> {
+        let bits = self.read_register(Register::DATA_FORMAT)?;
+        Ok(DataFormatFlags::from_bits_truncate(bits))
+    }
+
+    /// Read the `DATA_FORMAT` register from the device, replacing the cached
+    /// data format with its contents.
+    ///
+    /// Use this to resynchronize the driver after the register has been
+    /// changed out-of-band (e.g. by a brownout reset).
+    pub fn refresh_data_format(&mut self) -> Result<DataFormatFlags, Error<E>> {
+        let bits = self.read_register(Register::DATA_FORMAT)?;
+        self.data_format = DataFormatFlags::from_bits_truncate(bits);
+        Ok(self.data_format)
+    }
+
+    /// Write to the given register.
+    ///
+    /// Returns `ErrorKind::Param` without writing to the bus if the register
+    /// is read-only, or is `DATA_FORMAT`: the data format must be set with
+    /// [`Adxl343::data_format`] so the driver's cached copy stays in sync.
     // TODO: make this an internal API after enough functionality is wrapped
     pub fn write_register(&mut self, register: Register, value: u8) -> Result<(), Error<E>> {
-        // Preserve the invariant around self.data_format
-        assert_ne!(
-            register,
-            Register::DATA_FORMAT,
-            "set data format with Adxl343::data_format"
-        );
+        // Preserve the invariant around self.data_format, and never write to
+        // read-only registers
+        if register == Register::DATA_FORMAT || register.read_only() {
+            return Err(Error::new(ErrorKind::Param));
+        }
 
-        debug_assert!(!register.read_only(), "can't write to read-only register");
-        self.i2c.write(ADDRESS, &[register.addr(), value])?;
+        self.bus_write(&[register.addr(), value])?;
         Ok(())
     }
 
-    /// Write to a given register, then read the result
+    /// Set the number of times a failed bus transaction is retried before
+    /// the error is returned, e.g. to ride out occasional NACKs on a long
+    /// I2C cable. Defaults to 0 (no retries).
+    ///
+    /// Every register read and write is retried, so a transaction which
+    /// fails after the device has responded may be repeated: a retried read
+    /// of `INT_SOURCE` or the data registers may miss latched interrupts or
+    /// a sample consumed by the failed attempt.
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Get the number of times a failed bus transaction is retried
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
+
+    /// Write `bytes` to the device, retrying on failure
+    fn bus_write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let mut attempts = 0;
+
+        loop {
+            match self.i2c.write(self.address, bytes) {
+                Err(_) if attempts < self.retries => attempts += 1,
+                result => return result,
+            }
+        }
+    }
+
+    /// Write to a given register, then read the result.
+    ///
+    /// Fills `buffer` from consecutive registers starting at `register`:
+    ///
+    /// ```ignore
+    /// let mut offsets = [0u8; 3];
+    /// adxl343.write_read_register(Register::OFSX, &mut offsets)?;
+    /// ```
+    ///
+    /// Over I2C the device increments the register address automatically
+    /// after each byte. SPI is not supported by this driver: an SPI host
+    /// would also need to set the multiple-byte (`MB`, 0x40) bit alongside
+    /// the read bit (0x80), or every byte would come from `register`.
     // TODO: make this an internal API after enough functionality is wrapped
     pub fn write_read_register(&mut self, register: Register, buffer: &mut [u8]) -> Result<(), E> {
-        self.i2c.write_read(ADDRESS, &[register.addr()], buffer)
+        let mut attempts = 0;
+
+        loop {
+            match self
+                .i2c
+                .write_read(self.address, &[register.addr()], buffer)
+            {
+                Err(_) if attempts < self.retries => attempts += 1,
+                result => return result,
+            }
+        }
+    }
+
+    /// Read consecutive registers starting at `start` into `buf` in a single
+    /// multi-byte transaction (see [`Adxl343::write_read_register`]), e.g. to
+    /// read a block of configuration registers:
+    ///
+    /// ```ignore
+    /// // THRESH_ACT, THRESH_INACT, TIME_INACT, ACT_INACT_CTL, THRESH_FF, TIME_FF
+    /// let mut block = [0u8; 6];
+    /// adxl343.read_registers(Register::THRESH_ACT, &mut block)?;
+    /// ```
+    ///
+    /// Note that a block which includes `INT_SOURCE` or the data registers
+    /// has the same side effects as reading them individually.
+    ///
+    /// Returns `ErrorKind::Param` without reading from the bus if the block
+    /// would extend past `FIFO_STATUS`, the last register.
+    pub fn read_registers(&mut self, start: Register, buf: &mut [u8]) -> Result<(), Error<E>> {
+        let end = usize::from(start.addr()) + buf.len();
+
+        if end > usize::from(Register::FIFO_STATUS.addr()) + 1 {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        self.write_read_register(start, buf)?;
+        Ok(())
+    }
+
+    /// Read a single byte from the given register
+    pub fn read_u8(&mut self, register: Register) -> Result<u8, Error<E>> {
+        Ok(self.read_register(register)?)
+    }
+
+    /// Read the `DEVID` register and classify the device.
+    ///
+    /// Unlike the device ID check performed by [`Adxl343::new`], an
+    /// unrecognized ID is not an error: it is returned as
+    /// [`DeviceIdentity::Unknown`] along with the value that was read.
+    pub fn identify(&mut self) -> Result<DeviceIdentity, Error<E>> {
+        Ok(self.get_device_id()?.into())
+    }
+
+    /// Read the `DEVID` register and check it matches [`DEVICE_ID`]
+    pub fn verify_device_id(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.get_device_id()? == DEVICE_ID)
+    }
+
+    /// Check whether the device is present and responding.
+    ///
+    /// Performs a single read of the `DEVID` register and nothing else, so it
+    /// is safe to call between data reads: it does not clear latched
+    /// interrupts (`INT_SOURCE`) or disturb the FIFO. Bus errors are reported
+    /// as the device not being alive.
+    pub fn is_alive(&mut self) -> bool {
+        self.get_device_id().ok() == Some(DEVICE_ID)
+    }
+
+    /// Get the I2C address the driver is communicating with
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Consume the driver and return the underlying I2C bus, e.g. to hand it
+    /// to another driver.
+    ///
+    /// The device is left in its current configuration.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Wait for the turn-on time plus one sample period at the cached data
+    /// rate, using the delay given to [`Adxl343::new_with_delay`]
+    pub(crate) fn settle(&mut self) {
+        let period_us = rate_code_period_us(self.data_rate.code(), 1) + TURN_ON_TIME_US;
+        self.delay.delay_us(period_us as u32);
     }
 
     /// Get the device ID
     fn get_device_id(&mut self) -> Result<u8, E> {
-        let input = [Register::DEVID.addr()];
-        let mut output = [0u8];
-        self.i2c.write_read(ADDRESS, &input, &mut output)?;
-        Ok(output[0])
+        self.read_register(Register::DEVID)
+    }
+
+    /// Read a single byte from the given register
+    fn read_register(&mut self, register: Register) -> Result<u8, E> {
+        let mut buffer = [0u8];
+        self.write_read_register(register, &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    /// Poll `INT_SOURCE` until the `DATA_READY` bit is set, returning
+    /// `ErrorKind::Mode` if no sample arrives (e.g. the device is in standby).
+    ///
+    /// Note that reading `INT_SOURCE` clears any latched tap, activity,
+    /// inactivity, and free-fall interrupts.
+    #[cfg(feature = "i16x3")]
+    fn wait_for_data_ready<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        for _ in 0..DATA_READY_TIMEOUT_MS {
+            if self.data_ready()? {
+                return Ok(());
+            }
+
+            delay.delay_ms(1);
+        }
+
+        Err(Error::new(ErrorKind::Mode))
     }
 
-    /// Write to a given register, then read a `i16` result
+    /// Check whether a new sample is available by reading the `DATA_READY`
+    /// bit of `INT_SOURCE`.
+    ///
+    /// The bit is set whether or not the `DATA_READY` interrupt is enabled,
+    /// and is cleared by reading the data registers. Note that reading
+    /// `INT_SOURCE` clears any latched tap, activity, inactivity, and
+    /// free-fall interrupts.
+    pub fn data_ready(&mut self) -> Result<bool, Error<E>> {
+        let source = Interrupts::from_bits_truncate(self.read_register(Register::INT_SOURCE)?);
+        Ok(source.contains(Interrupts::DATA_READY))
+    }
+
+    /// Wait until a new sample is available, then get a raw acceleration
+    /// reading, so the same sample is never returned twice.
+    ///
+    /// Polls `DATA_READY` every millisecond, returning `ErrorKind::Mode` if no
+    /// sample arrives within one sample period at the slowest data rate (e.g.
+    /// because the device is in standby).
+    #[cfg(feature = "i16x3")]
+    pub fn accel_raw_when_ready<D>(&mut self, delay: &mut D) -> Result<I16x3, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.wait_for_data_ready(delay)?;
+        self.accel_raw()
+    }
+
+    /// Get a raw acceleration reading, interpreting the data registers with
+    /// the given byte order.
+    ///
+    /// This is a diagnostic escape hatch for checking the byte order used by
+    /// a particular (possibly compatible) part at runtime.
+    ///
+    /// Returns [`Adxl343Error::JustifyMismatch`] if `JUSTIFY` is set in the
+    /// data format.
+    #[cfg(feature = "i16x3")]
+    pub fn accel_raw_with_endian(&mut self, endian: Endian) -> Result<I16x3, Adxl343Error<E>> {
+        if self.data_format.contains(DataFormatFlags::JUSTIFY) {
+            return Err(Adxl343Error::JustifyMismatch);
+        }
+
+        self.read_data_i16x3(endian).map_err(Adxl343Error::Bus)
+    }
+
+    /// Get both the raw and normalized (±g) acceleration from a single burst
+    /// read of the data registers, so the two are guaranteed to describe the
+    /// same sample.
+    ///
+    /// Returns [`Adxl343Error::JustifyMismatch`] if `JUSTIFY` is set in the
+    /// data format.
+    #[cfg(feature = "i16x3")]
+    pub fn accel_both(&mut self) -> Result<(I16x3, F32x3), Adxl343Error<E>> {
+        let raw = self.accel_raw_with_endian(Endian::Little)?;
+        Ok((raw, self.data_format.raw_to_g(raw)))
+    }
+
+    /// Get a raw full resolution acceleration reading.
+    ///
+    /// In full resolution mode the output has up to 13 significant bits at
+    /// ±16g, right-justified and sign-extended by the device to 16 bits, so
+    /// the counts are read as-is. The scale factor is 3.9 mg/LSB (nominally
+    /// 4 mg/LSB, i.e. 256 LSB/g) at every range, so ±16g reads as ±4096.
+    ///
+    /// Returns `ErrorKind::Mode` if `FULL_RES` is not set in the data format,
+    /// or if `JUSTIFY` is (as for `accel_raw`).
+    #[cfg(feature = "i16x3")]
+    pub fn accel_raw_full_res(&mut self) -> Result<I16x3, Error<E>> {
+        if !self.data_format.contains(DataFormatFlags::FULL_RES) {
+            return Err(Error::new(ErrorKind::Mode));
+        }
+
+        self.accel_raw()
+    }
+
+    /// Get a raw acceleration reading as `f32` counts, without scaling to g.
+    ///
+    /// Useful where absolute scaling doesn't matter (e.g. FFTs), as the counts
+    /// are converted exactly.
+    #[cfg(feature = "i16x3")]
+    pub fn accel_raw_f32(&mut self) -> Result<F32x3, Error<E>> {
+        let raw: I16x3 = self.accel_raw()?;
+        Ok(F32x3::new(raw.x.into(), raw.y.into(), raw.z.into()))
+    }
+
+    /// Read the six data registers (`DATAX0` through `DATAZ1`) in a single
+    /// burst read and return the raw bytes without interpreting them.
+    ///
+    /// Each axis is a pair of bytes, least significant first, in the format
+    /// selected by the data format (e.g. left-justified if `JUSTIFY` is set).
+    pub fn read_raw_bytes(&mut self) -> Result<[u8; 6], Error<E>> {
+        Ok(self.read_data_registers()?)
+    }
+
+    /// Get the acceleration in m/s², i.e. the normalized reading multiplied
+    /// by [`STANDARD_GRAVITY`]
+    #[cfg(feature = "i16x3")]
+    pub fn accel_mps2(&mut self) -> Result<F32x3, Error<E>> {
+        self.accel_mps2_with_g(STANDARD_GRAVITY)
+    }
+
+    /// Get the acceleration in m/s², i.e. the normalized reading multiplied
+    /// by `g0`, e.g. the local gravitational acceleration in place of
+    /// [`STANDARD_GRAVITY`]
+    #[cfg(feature = "i16x3")]
+    pub fn accel_mps2_with_g(&mut self, g0: f32) -> Result<F32x3, Error<E>> {
+        let g = self.accel_norm()?;
+        Ok(F32x3::new(g.x * g0, g.y * g0, g.z * g0))
+    }
+
+    /// Get a normalized ±g reading like [`Accelerometer::accel_norm`], along
+    /// with whether the reading is saturated.
+    ///
+    /// Accelerations beyond the selected range are clipped to the range
+    /// limit rather than rolling over to the opposite sign (from the
+    /// `DATA_FORMAT` description in the data sheet: "All data, except that
+    /// for the ±16 g range, must be clipped to avoid rollover"), so a reading
+    /// at the limit on any axis may understate the true acceleration. Returns
+    /// `true` in that case, e.g. to detect impacts which need a larger range.
+    #[cfg(feature = "i16x3")]
+    pub fn accel_norm_checked(&mut self) -> Result<(F32x3, bool), Error<E>> {
+        let raw: I16x3 = self.accel_raw()?;
+        Ok((
+            self.data_format.raw_to_g(raw),
+            self.data_format.is_saturated(raw),
+        ))
+    }
+
+    /// Get the acceleration in milli-g using only integer arithmetic, for
+    /// targets without a floating point unit.
+    ///
+    /// Raw counts are scaled by the sensitivity for the cached data format
+    /// (see [`DataFormatRange::lsb_per_g`]), truncating toward zero.
+    #[cfg(feature = "i16x3")]
+    pub fn accel_milli_g(&mut self) -> Result<[i32; 3], Error<E>> {
+        let raw: I16x3 = self.accel_raw()?;
+        let counts_per_g = i32::from(
+            self.data_format
+                .range()
+                .counts_per_g(self.full_resolution()),
+        );
+        let milli_g = |counts: i16| i32::from(counts) * 1000 / counts_per_g;
+
+        Ok([milli_g(raw.x), milli_g(raw.y), milli_g(raw.z)])
+    }
+
+    /// Read all six data registers in a single multi-byte read, as an `I16x3`
     ///
     /// From the ADXL343 data sheet (p.25):
     /// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
@@ -147,99 +860,122 @@ where
     /// "The output data is twos complement, with DATAx0 as the least
     /// significant byte and DATAx1 as the most significant byte"
     #[cfg(feature = "i16x3")]
-    fn write_read_i16(&mut self, register: Register) -> Result<i16, E> {
-        let mut buffer = [0u8; 2];
-        self.write_read_register(register, &mut buffer)?;
-        Ok(i16::from_be_bytes(buffer))
+    fn read_data_i16x3(&mut self, endian: Endian) -> Result<I16x3, E> {
+        let bytes = self.read_data_registers()?;
+        let axis = |lo: u8, hi: u8| match endian {
+            Endian::Little => i16::from_le_bytes([lo, hi]),
+            Endian::Big => i16::from_be_bytes([lo, hi]),
+        };
+
+        Ok(I16x3::new(
+            axis(bytes[0], bytes[1]),
+            axis(bytes[2], bytes[3]),
+            axis(bytes[4], bytes[5]),
+        ))
     }
 
-    /// Write to a given register, then read a `u16` result
+    /// Read all six data registers (`DATAX0` through `DATAZ1`) in a single
+    /// multi-byte read.
     ///
-    /// Used for reading `JUSTIFY`-mode data. From the ADXL343 data sheet (p.25):
-    /// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
-    ///
-    /// "A setting of 1 in the justify bit selects left-justified (MSB) mode,
-    /// and a setting of 0 selects right-justified mode with sign extension."
-    #[cfg(feature = "u16x3")]
-    fn write_read_u16(&mut self, register: Register) -> Result<u16, E> {
-        let mut buffer = [0u8; 2];
-        self.write_read_register(register, &mut buffer)?;
-        Ok(u16::from_le_bytes(buffer))
+    /// From the ADXL343 data sheet (p.25): "It is recommended that a
+    /// multiple-byte read of all registers be performed to prevent a change
+    /// in data between reads of sequential registers."
+    fn read_data_registers(&mut self) -> Result<[u8; 6], E> {
+        let mut buffer = [0u8; 6];
+        self.write_read_register(Register::DATAX0, &mut buffer)?;
+        Ok(buffer)
     }
 }
 
 #[cfg(feature = "i16x3")]
-impl<I2C, E> Accelerometer for Adxl343<I2C>
+impl<I2C, DELAY, E> Accelerometer for Adxl343<I2C, DELAY>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
     E: Debug,
 {
     type Error = E;
 
     /// Get normalized ±g reading from the accelerometer.
+    ///
+    /// Readings beyond the selected range are clipped to the range limit:
+    /// see [`Adxl343::accel_norm_checked`] to detect this.
     fn accel_norm(&mut self) -> Result<F32x3, Error<E>> {
         let raw_data: I16x3 = self.accel_raw()?;
-        let range: f32 = self.data_format.range().into();
-
-        let x = (raw_data.x as f32 / core::i16::MAX as f32) * range;
-        let y = (raw_data.y as f32 / core::i16::MAX as f32) * range;
-        let z = (raw_data.z as f32 / core::i16::MAX as f32) * range;
-
-        Ok(F32x3::new(x, y, z))
+        Ok(self.data_format.raw_to_g(raw_data))
     }
 
     /// Get sample rate of accelerometer in Hz.
     ///
-    /// This is presently hardcoded to 100Hz - the default data rate.
+    /// This reads the rate code from the `BW_RATE` register, so it reflects
+    /// the device's actual configuration even if it was changed out-of-band,
+    /// and updates the cached [`Adxl343::data_rate`] to match.
     /// See "Register 0x2C - BW_RATE" documentation in ADXL343 data sheet (p.23):
     /// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
-    ///
-    /// "The default value is 0x0A, which translates to a 100 Hz output data rate."
     fn sample_rate(&mut self) -> Result<f32, Error<Self::Error>> {
-        Ok(100.0)
+        let code = self.read_register(Register::BW_RATE)? & RATE_MASK;
+        self.data_rate = DataRate::from_code(code);
+        Ok(self.data_rate.into())
     }
 }
 
 #[cfg(feature = "i16x3")]
-impl<I2C, E> RawAccelerometer<I16x3> for Adxl343<I2C>
+impl<I2C, DELAY, E> RawAccelerometer<I16x3> for Adxl343<I2C, DELAY>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
     E: Debug,
 {
     type Error = E;
 
     /// Get acceleration reading from the accelerometer
+    ///
+    /// Returns `ErrorKind::Mode` if `JUSTIFY` is set in the data format, as
+    /// left-justified data must be read as `U16x3`. This is the only mode
+    /// error this method returns, so it always means a justify mismatch (see
+    /// [`Adxl343Error::JustifyMismatch`]).
     fn accel_raw(&mut self) -> Result<I16x3, Error<E>> {
-        if self.data_format.contains(DataFormatFlags::JUSTIFY) {
-            return Err(Error::new(ErrorKind::Mode));
-        }
-
-        let x = self.write_read_i16(Register::DATAX0)?;
-        let y = self.write_read_i16(Register::DATAY0)?;
-        let z = self.write_read_i16(Register::DATAZ0)?;
-
-        Ok(I16x3::new(x, y, z))
+        self.data_format.check_justify(false)?;
+        Ok(self.read_data_i16x3(Endian::Little)?)
     }
 }
 
 #[cfg(feature = "u16x3")]
-impl<I2C, E> RawAccelerometer<U16x3> for Adxl343<I2C>
+impl<I2C, DELAY, E> RawAccelerometer<U16x3> for Adxl343<I2C, DELAY>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
     E: Debug,
 {
     type Error = E;
 
     /// Get acceleration reading from the accelerometer
+    ///
+    /// Used for reading `JUSTIFY`-mode data. From the ADXL343 data sheet (p.25):
+    /// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
+    ///
+    /// "A setting of 1 in the justify bit selects left-justified (MSB) mode,
+    /// and a setting of 0 selects right-justified mode with sign extension."
+    ///
+    /// Justification only moves the value within the 16-bit word: in either
+    /// mode DATAx0 holds the least significant byte and DATAx1 the most
+    /// significant byte, so each axis is read least significant byte first,
+    /// as for `I16x3`. The most significant bit of each left-justified value
+    /// is the twos complement sign bit, and the unused low bits are zero, so
+    /// the value can be reinterpreted as an `i16` scaled by 2^(16 - bits).
+    ///
+    /// Returns `ErrorKind::Mode` if `JUSTIFY` is not set in the data format.
+    /// This is the only mode error this method returns, so it always means a
+    /// justify mismatch (see [`Adxl343Error::JustifyMismatch`]).
     fn accel_raw(&mut self) -> Result<U16x3, Error<E>> {
-        if !self.data_format.contains(DataFormatFlags::JUSTIFY) {
-            return Err(Error::new(ErrorKind::Mode));
-        }
+        self.data_format.check_justify(true)?;
 
-        let x = self.write_read_u16(Register::DATAX0)?;
-        let y = self.write_read_u16(Register::DATAY0)?;
-        let z = self.write_read_u16(Register::DATAZ0)?;
+        let bytes = self.read_data_registers()?;
 
-        Ok(U16x3::new(x, y, z))
+        Ok(U16x3::new(
+            u16::from_le_bytes([bytes[0], bytes[1]]),
+            u16::from_le_bytes([bytes[2], bytes[3]]),
+            u16::from_le_bytes([bytes[4], bytes[5]]),
+        ))
     }
 }
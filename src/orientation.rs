@@ -0,0 +1,137 @@
+//! Orientation helpers computed from normalized readings
+
+use crate::Adxl343;
+use accelerometer::{vector::F32x3, Accelerometer, Error};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+use libm::{atan2f, sqrtf};
+
+/// Which face of the device is pointing up, returned by
+/// `Adxl343::orientation`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Orientation {
+    /// +X axis pointing up
+    XUp,
+
+    /// +X axis pointing down
+    XDown,
+
+    /// +Y axis pointing up
+    YUp,
+
+    /// +Y axis pointing down
+    YDown,
+
+    /// +Z axis pointing up (e.g. lying flat, component side up)
+    ZUp,
+
+    /// +Z axis pointing down
+    ZDown,
+}
+
+impl Orientation {
+    /// Classify a normalized (±g) reading by its dominant axis.
+    ///
+    /// Returns `None` if the dominant axis reads less than `threshold` g in
+    /// magnitude, e.g. while the device is tilted between two faces or in
+    /// free fall.
+    pub fn from_g(g: F32x3, threshold: f32) -> Option<Orientation> {
+        let abs = |v: f32| if v < 0.0 { -v } else { v };
+        let (x, y, z) = (abs(g.x), abs(g.y), abs(g.z));
+
+        let (value, up, down) = if x >= y && x >= z {
+            (g.x, Orientation::XUp, Orientation::XDown)
+        } else if y >= z {
+            (g.y, Orientation::YUp, Orientation::YDown)
+        } else {
+            (g.z, Orientation::ZUp, Orientation::ZDown)
+        };
+
+        if value >= threshold {
+            Some(up)
+        } else if value <= -threshold {
+            Some(down)
+        } else {
+            None
+        }
+    }
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Get the inclination of the device as `(pitch, roll)` in radians,
+    /// computed from a single normalized reading.
+    ///
+    /// With the device lying flat (+Z axis pointing up) both angles are zero.
+    /// Pitch is the rotation about the Y axis, `atan2(x, sqrt(y² + z²))`, and
+    /// is positive when the +X axis is tilted upwards. Roll is the rotation
+    /// about the X axis, `atan2(y, z)`, and is positive when the +Y axis is
+    /// tilted upwards.
+    ///
+    /// Only meaningful while the device is static, i.e. when gravity is the
+    /// only acceleration acting on it.
+    pub fn inclination(&mut self) -> Result<(f32, f32), Error<E>> {
+        let g = self.accel_norm()?;
+        let pitch = atan2f(g.x, sqrtf(g.y * g.y + g.z * g.z));
+        let roll = atan2f(g.y, g.z);
+        Ok((pitch, roll))
+    }
+
+    /// Get the magnitude of the acceleration in g, `sqrt(x² + y² + z²)`,
+    /// computed from a single normalized reading.
+    ///
+    /// Reads about 1g while the device is static, whatever its orientation,
+    /// so departures from 1g indicate motion, impacts, or free fall.
+    pub fn accel_magnitude(&mut self) -> Result<f32, Error<E>> {
+        let g = self.accel_norm()?;
+        Ok(sqrtf(g.x * g.x + g.y * g.y + g.z * g.z))
+    }
+
+    /// Classify which face of the device is pointing up from the dominant
+    /// axis of a single normalized reading (see [`Orientation::from_g`]).
+    ///
+    /// This is a convenience over [`Accelerometer::accel_norm`]. Returns
+    /// `None` if the dominant axis reads less than `threshold` g: a
+    /// threshold of around 0.8g tolerates moderate tilt while rejecting
+    /// readings taken between two faces. Only meaningful while the device
+    /// is static.
+    pub fn orientation(&mut self, threshold: f32) -> Result<Option<Orientation>, Error<E>> {
+        let g = self.accel_norm()?;
+        Ok(Orientation::from_g(g, threshold))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Orientation;
+    use accelerometer::vector::F32x3;
+
+    #[test]
+    fn from_g_classifies_dominant_axis() {
+        let cases = [
+            (F32x3::new(0.9, 0.1, 0.2), Orientation::XUp),
+            (F32x3::new(-0.9, 0.1, 0.2), Orientation::XDown),
+            (F32x3::new(0.1, 0.95, -0.3), Orientation::YUp),
+            (F32x3::new(0.1, -0.95, -0.3), Orientation::YDown),
+            (F32x3::new(0.0, 0.0, 1.0), Orientation::ZUp),
+            (F32x3::new(0.2, -0.1, -1.0), Orientation::ZDown),
+        ];
+
+        for (g, orientation) in cases.iter() {
+            assert_eq!(Orientation::from_g(*g, 0.8), Some(*orientation));
+        }
+    }
+
+    #[test]
+    fn from_g_rejects_readings_below_threshold() {
+        // Tilted 45° between two faces
+        assert_eq!(Orientation::from_g(F32x3::new(0.0, 0.7, 0.7), 0.8), None);
+        // Free fall
+        assert_eq!(Orientation::from_g(F32x3::new(0.0, 0.0, 0.0), 0.8), None);
+    }
+}
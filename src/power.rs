@@ -0,0 +1,239 @@
+//! Power control (`POWER_CTL`)
+
+use crate::{
+    register::{mg_to_thresh, Register},
+    Adxl343,
+};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// `POWER_CTL` Link bit: serially links activity and inactivity detection
+pub(crate) const LINK: u8 = 0b0010_0000;
+
+/// `POWER_CTL` AUTO_SLEEP bit: sleep on inactivity, wake on activity
+pub(crate) const AUTO_SLEEP: u8 = 0b0001_0000;
+
+/// `POWER_CTL` Measure bit: 1 = measurement mode, 0 = standby
+pub(crate) const MEASURE: u8 = 0b0000_1000;
+
+/// `POWER_CTL` Sleep bit: 1 = sleep mode
+pub(crate) const SLEEP: u8 = 0b0000_0100;
+
+/// Mask for the Wakeup bits of `POWER_CTL`
+const WAKEUP_MASK: u8 = 0b0000_0011;
+
+/// Frequency of readings in sleep mode, set by the Wakeup bits of
+/// `POWER_CTL` (data sheet p.24, Table 18)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[repr(u8)]
+pub enum WakeupRate {
+    /// 8 Hz (default)
+    #[default]
+    Hz8 = 0b00,
+
+    /// 4 Hz
+    Hz4 = 0b01,
+
+    /// 2 Hz
+    Hz2 = 0b10,
+
+    /// 1 Hz
+    Hz1 = 0b11,
+}
+
+impl WakeupRate {
+    /// Get the Wakeup bits for this rate
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Get the wakeup rate from the contents of `POWER_CTL`
+    pub fn from_bits(power_ctl: u8) -> WakeupRate {
+        match power_ctl & WAKEUP_MASK {
+            0b00 => WakeupRate::Hz8,
+            0b01 => WakeupRate::Hz4,
+            0b10 => WakeupRate::Hz2,
+            _ => WakeupRate::Hz1,
+        }
+    }
+}
+
+/// Auto-sleep configuration, applied with `Adxl343::configure_auto_sleep`.
+///
+/// With auto-sleep enabled the device switches to sleep mode when inactivity
+/// is detected and back to normal operation when activity is detected.
+/// Activity and inactivity detection must also be enabled on at least one
+/// axis in `ACT_INACT_CTL` for this to take effect.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct AutoSleepConfig {
+    /// Enable automatic switching to sleep mode (`POWER_CTL` AUTO_SLEEP bit).
+    /// Requires `link`.
+    pub auto_sleep: bool,
+
+    /// Link activity and inactivity detection, so that each is only detected
+    /// after the other (`POWER_CTL` Link bit)
+    pub link: bool,
+
+    /// Activity threshold in mg (`THRESH_ACT`, 62.5 mg/LSB)
+    pub activity_threshold_mg: u16,
+
+    /// Inactivity threshold in mg (`THRESH_INACT`, 62.5 mg/LSB)
+    pub inactivity_threshold_mg: u16,
+
+    /// Inactivity time (`TIME_INACT`): 1 s/LSB
+    pub inactivity_time: u8,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Switch between measurement mode (`true`) and standby (`false`) using
+    /// the Measure bit of `POWER_CTL`.
+    ///
+    /// When entering measurement mode, waits for the device to settle if the
+    /// driver was created with [`Adxl343::new_with_delay`].
+    ///
+    /// Standby minimizes power consumption: no measurements are made.
+    /// Performs a read-modify-write, so the Link, AUTO_SLEEP, Sleep, and
+    /// Wakeup bits are preserved.
+    pub fn set_measuring(&mut self, on: bool) -> Result<(), Error<E>> {
+        let power_ctl = self.read_register(Register::POWER_CTL)?;
+
+        let power_ctl = if on {
+            power_ctl | MEASURE
+        } else {
+            power_ctl & !MEASURE
+        };
+
+        self.write_register(Register::POWER_CTL, power_ctl)?;
+
+        if on {
+            self.settle();
+        }
+
+        Ok(())
+    }
+
+    /// Is the device in measurement mode (as opposed to standby)?
+    pub fn is_measuring(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_register(Register::POWER_CTL)? & MEASURE != 0)
+    }
+
+    /// Put the device into sleep mode by setting the Sleep bit of
+    /// `POWER_CTL`, suppressing `DATA_READY`, stopping transmission of data
+    /// to the FIFO, and reducing the sampling rate to the wakeup rate (see
+    /// [`Adxl343::set_wakeup_rate`]).
+    ///
+    /// Performs a read-modify-write, preserving the other bits.
+    pub fn sleep(&mut self) -> Result<(), Error<E>> {
+        let power_ctl = self.read_register(Register::POWER_CTL)?;
+        self.write_register(Register::POWER_CTL, power_ctl | SLEEP)
+    }
+
+    /// Return the device from sleep mode to normal measurement.
+    ///
+    /// Rather than only clearing the Sleep bit, this follows the sequence
+    /// recommended by the data sheet (p.24): "When clearing the sleep bit,
+    /// it is recommended that the part be placed into standby mode and then
+    /// set back to measurement mode with a subsequent write." The Sleep and
+    /// Measure bits are cleared together, then Measure is set again. Waits
+    /// for the device to settle if the driver was created with
+    /// [`Adxl343::new_with_delay`].
+    pub fn wake(&mut self) -> Result<(), Error<E>> {
+        let power_ctl = self.read_register(Register::POWER_CTL)? & !(SLEEP | MEASURE);
+        self.write_register(Register::POWER_CTL, power_ctl)?;
+        self.write_register(Register::POWER_CTL, power_ctl | MEASURE)?;
+        self.settle();
+        Ok(())
+    }
+
+    /// Set how often the device takes readings while in sleep mode, using
+    /// the Wakeup bits of `POWER_CTL`.
+    ///
+    /// Performs a read-modify-write, so the Link, AUTO_SLEEP, Measure, and
+    /// Sleep bits are preserved.
+    pub fn set_wakeup_rate(&mut self, rate: WakeupRate) -> Result<(), Error<E>> {
+        let power_ctl = self.read_register(Register::POWER_CTL)?;
+        self.write_register(
+            Register::POWER_CTL,
+            (power_ctl & !WAKEUP_MASK) | rate.bits(),
+        )
+    }
+
+    /// Get the sleep mode reading frequency from `POWER_CTL`
+    pub fn wakeup_rate(&mut self) -> Result<WakeupRate, Error<E>> {
+        Ok(WakeupRate::from_bits(
+            self.read_register(Register::POWER_CTL)?,
+        ))
+    }
+
+    /// Put the device into a quiescent, low-power, non-interrupting state,
+    /// e.g. as part of recovering from a fault.
+    ///
+    /// In order, this:
+    ///
+    /// 1. Disables all interrupts (`INT_ENABLE` = 0)
+    /// 2. Enters standby, with sleep, auto-sleep, and link disabled
+    ///    (`POWER_CTL` = 0)
+    /// 3. Puts the FIFO in bypass mode, discarding its contents (`FIFO_CTL` = 0)
+    ///
+    /// Registers are written without being read first, and every step is
+    /// attempted even if an earlier one fails, so this is tolerant of a
+    /// partially working bus. Returns the first error encountered, if any.
+    pub fn safe_shutdown(&mut self) -> Result<(), Error<E>> {
+        let int_enable = self.write_register(Register::INT_ENABLE, 0);
+        let power_ctl = self.write_register(Register::POWER_CTL, 0);
+        let fifo_ctl = self.write_register(Register::FIFO_CTL, 0);
+
+        int_enable.and(power_ctl).and(fifo_ctl)
+    }
+
+    /// Configure auto-sleep, along with the activity and inactivity
+    /// thresholds which drive it.
+    ///
+    /// Per the data sheet (p.23), auto-sleep only functions with the Link bit
+    /// set: a config with `auto_sleep` but not `link` returns `ErrorKind::Mode`.
+    /// Thresholds are rounded to the nearest 62.5 mg LSB. Thresholds above
+    /// 15.9375g don't fit in the registers and return `ErrorKind::Param`.
+    ///
+    /// The device is placed in standby while `POWER_CTL` is updated, as the
+    /// data sheet recommends when changing AUTO_SLEEP, then returned to
+    /// measurement mode if it was measuring.
+    pub fn configure_auto_sleep(&mut self, config: AutoSleepConfig) -> Result<(), Error<E>> {
+        if config.auto_sleep && !config.link {
+            return Err(Error::new(ErrorKind::Mode));
+        }
+
+        let activity = mg_to_thresh(config.activity_threshold_mg)
+            .ok_or_else(|| Error::new(ErrorKind::Param))?;
+        let inactivity = mg_to_thresh(config.inactivity_threshold_mg)
+            .ok_or_else(|| Error::new(ErrorKind::Param))?;
+
+        self.write_register(Register::THRESH_ACT, activity)?;
+        self.write_register(Register::THRESH_INACT, inactivity)?;
+        self.write_register(Register::TIME_INACT, config.inactivity_time)?;
+
+        let power_ctl = self.read_register(Register::POWER_CTL)?;
+        let mut standby = power_ctl & !(MEASURE | LINK | AUTO_SLEEP);
+
+        if config.link {
+            standby |= LINK;
+        }
+
+        if config.auto_sleep {
+            standby |= AUTO_SLEEP;
+        }
+
+        self.write_register(Register::POWER_CTL, standby)?;
+
+        if power_ctl & MEASURE != 0 {
+            self.write_register(Register::POWER_CTL, standby | MEASURE)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Output data rate (`BW_RATE`) support
+#![allow(non_camel_case_types)]
+
+/// Mask for the rate code bits of `BW_RATE`
+pub(crate) const RATE_MASK: u8 = 0b0000_1111;
+
+/// `LOW_POWER` bit of `BW_RATE`
+pub(crate) const LOW_POWER: u8 = 0b0001_0000;
+
+/// Output data rate, as set by the rate code bits of `Register::BW_RATE`.
+///
+/// See "Register 0x2C - BW_RATE" and Table 7 in the ADXL343 data sheet (p.23):
+/// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
+///
+/// The default is 100 Hz: "The default value is 0x0A, which translates to a
+/// 100 Hz output data rate."
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[repr(u8)]
+pub enum DataRate {
+    /// 0.10 Hz
+    HZ_0_10 = 0x0,
+
+    /// 0.20 Hz
+    HZ_0_20 = 0x1,
+
+    /// 0.39 Hz
+    HZ_0_39 = 0x2,
+
+    /// 0.78 Hz
+    HZ_0_78 = 0x3,
+
+    /// 1.56 Hz
+    HZ_1_56 = 0x4,
+
+    /// 3.13 Hz
+    HZ_3_13 = 0x5,
+
+    /// 6.25 Hz
+    HZ_6_25 = 0x6,
+
+    /// 12.5 Hz
+    HZ_12_5 = 0x7,
+
+    /// 25 Hz
+    HZ_25 = 0x8,
+
+    /// 50 Hz
+    HZ_50 = 0x9,
+
+    /// 100 Hz (default)
+    #[default]
+    HZ_100 = 0xA,
+
+    /// 200 Hz
+    HZ_200 = 0xB,
+
+    /// 400 Hz
+    HZ_400 = 0xC,
+
+    /// 800 Hz
+    HZ_800 = 0xD,
+
+    /// 1600 Hz
+    HZ_1600 = 0xE,
+
+    /// 3200 Hz
+    HZ_3200 = 0xF,
+}
+
+impl DataRate {
+    /// Get the `BW_RATE` rate code for this data rate
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Get the data rate for the given `BW_RATE` rate code. Bits outside the
+    /// rate code (e.g. `LOW_POWER`) are ignored.
+    pub fn from_code(code: u8) -> DataRate {
+        match code & RATE_MASK {
+            0x0 => DataRate::HZ_0_10,
+            0x1 => DataRate::HZ_0_20,
+            0x2 => DataRate::HZ_0_39,
+            0x3 => DataRate::HZ_0_78,
+            0x4 => DataRate::HZ_1_56,
+            0x5 => DataRate::HZ_3_13,
+            0x6 => DataRate::HZ_6_25,
+            0x7 => DataRate::HZ_12_5,
+            0x8 => DataRate::HZ_25,
+            0x9 => DataRate::HZ_50,
+            0xA => DataRate::HZ_100,
+            0xB => DataRate::HZ_200,
+            0xC => DataRate::HZ_400,
+            0xD => DataRate::HZ_800,
+            0xE => DataRate::HZ_1600,
+            _ => DataRate::HZ_3200,
+        }
+    }
+
+    /// Get the supported data rate nearest to `hz`.
+    ///
+    /// Returns `None` if `hz` is not a positive, finite number.
+    pub fn from_hz(hz: f32) -> Option<DataRate> {
+        if !hz.is_finite() || hz <= 0.0 {
+            return None;
+        }
+
+        let distance = |code: u8| {
+            let diff = f32::from(DataRate::from_code(code)) - hz;
+            if diff < 0.0 {
+                -diff
+            } else {
+                diff
+            }
+        };
+
+        let mut nearest = 0;
+
+        for code in 1..=RATE_MASK {
+            if distance(code) < distance(nearest) {
+                nearest = code;
+            }
+        }
+
+        Some(DataRate::from_code(nearest))
+    }
+
+    /// Can this data rate be used in low power mode?
+    ///
+    /// Per the data sheet (p.12, Table 8), low power mode is only available
+    /// for output data rates from 12.5 Hz to 400 Hz.
+    pub fn supports_low_power(self) -> bool {
+        let code = self.code();
+        code >= DataRate::HZ_12_5.code() && code <= DataRate::HZ_400.code()
+    }
+}
+
+impl From<DataRate> for f32 {
+    fn from(rate: DataRate) -> f32 {
+        match rate {
+            DataRate::HZ_0_10 => 0.10,
+            DataRate::HZ_0_20 => 0.20,
+            DataRate::HZ_0_39 => 0.39,
+            DataRate::HZ_0_78 => 0.78,
+            DataRate::HZ_1_56 => 1.56,
+            DataRate::HZ_3_13 => 3.13,
+            DataRate::HZ_6_25 => 6.25,
+            DataRate::HZ_12_5 => 12.5,
+            DataRate::HZ_25 => 25.0,
+            DataRate::HZ_50 => 50.0,
+            DataRate::HZ_100 => 100.0,
+            DataRate::HZ_200 => 200.0,
+            DataRate::HZ_400 => 400.0,
+            DataRate::HZ_800 => 800.0,
+            DataRate::HZ_1600 => 1600.0,
+            DataRate::HZ_3200 => 3200.0,
+        }
+    }
+}
+
+/// Get the sampling period in microseconds of `samples` samples at the given
+/// `BW_RATE` rate code.
+///
+/// Each rate code doubles the rate of the one below it, up to 3200 Hz for
+/// code 0xF (data sheet p.23, Table 7), so the period of one sample is
+/// `2^(15 - code) / 3200` seconds.
+pub(crate) fn rate_code_period_us(code: u8, samples: u64) -> u64 {
+    let shift = 15 - u32::from(code & RATE_MASK);
+    ((samples * 1_000_000) << shift) / 3200
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataRate;
+
+    #[test]
+    fn rate_codes_match_data_sheet_table() {
+        // Data sheet p.23, Table 7
+        let table = [
+            0.10, 0.20, 0.39, 0.78, 1.56, 3.13, 6.25, 12.5, 25.0, 50.0, 100.0, 200.0, 400.0, 800.0,
+            1600.0, 3200.0,
+        ];
+
+        for (code, hz) in table.iter().enumerate() {
+            assert_eq!(f32::from(DataRate::from_code(code as u8)), *hz);
+        }
+
+        // Bits above the rate code (e.g. LOW_POWER) are ignored
+        assert_eq!(DataRate::from_code(0x1A), DataRate::HZ_100);
+    }
+
+    #[test]
+    fn from_hz_selects_nearest_rate() {
+        assert_eq!(DataRate::from_hz(100.0), Some(DataRate::HZ_100));
+        assert_eq!(DataRate::from_hz(130.0), Some(DataRate::HZ_100));
+        assert_eq!(DataRate::from_hz(170.0), Some(DataRate::HZ_200));
+        assert_eq!(DataRate::from_hz(0.01), Some(DataRate::HZ_0_10));
+        assert_eq!(DataRate::from_hz(10_000.0), Some(DataRate::HZ_3200));
+    }
+
+    #[test]
+    fn from_hz_rejects_non_positive_or_non_finite_rates() {
+        assert_eq!(DataRate::from_hz(0.0), None);
+        assert_eq!(DataRate::from_hz(-100.0), None);
+        assert_eq!(DataRate::from_hz(f32::NAN), None);
+        assert_eq!(DataRate::from_hz(f32::INFINITY), None);
+    }
+}
@@ -1,12 +1,18 @@
 //! ADXL343 register addresses
 #![allow(non_camel_case_types, clippy::unreadable_literal)]
 
+#[cfg(feature = "i16x3")]
+use accelerometer::vector::{F32x3, I16x3};
+#[cfg(any(feature = "i16x3", feature = "u16x3"))]
+use accelerometer::{Error, ErrorKind};
 use bitflags::bitflags;
+use core::{convert::TryFrom, fmt::Debug};
 
 /// Register addresses
 /// Taken from the ADXL343 data sheet (Register Map, p.21)
 /// <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum Register {
     /// Device ID (Read Only)
@@ -253,28 +259,180 @@ pub enum Register {
 
 impl Register {
     /// Get register address
-    pub fn addr(self) -> u8 {
+    pub const fn addr(self) -> u8 {
         self as u8
     }
 
     /// Is the register read-only?
-    pub fn read_only(self) -> bool {
-        match self {
+    pub const fn read_only(self) -> bool {
+        matches!(
+            self,
             Register::DEVID
-            | Register::ACT_TAP_STATUS
-            | Register::INT_SOURCE
-            | Register::DATAX0
-            | Register::DATAX1
-            | Register::DATAY0
-            | Register::DATAY1
-            | Register::DATAZ0
-            | Register::DATAZ1
-            | Register::FIFO_STATUS => true,
-            _ => false,
+                | Register::ACT_TAP_STATUS
+                | Register::INT_SOURCE
+                | Register::DATAX0
+                | Register::DATAX1
+                | Register::DATAY0
+                | Register::DATAY1
+                | Register::DATAZ0
+                | Register::DATAZ1
+                | Register::FIFO_STATUS
+        )
+    }
+
+    /// Is the register writable?
+    pub const fn is_writable(self) -> bool {
+        !self.read_only()
+    }
+
+    /// Get the scale factor of the register, as documented in the data
+    /// sheet, or `None` if its value isn't a scaled quantity.
+    ///
+    /// Covers the threshold (`THRESH_*`), tap timing (`DUR`, `LATENT`,
+    /// `WINDOW`), inactivity and free-fall time (`TIME_*`), and offset
+    /// (`OFS*`) registers.
+    pub const fn scale_factor(self) -> Option<ScaleFactor> {
+        match self {
+            Register::THRESH_TAP
+            | Register::THRESH_ACT
+            | Register::THRESH_INACT
+            | Register::THRESH_FF => Some(ScaleFactor::unsigned(ScaleUnit::MilliG, 62.5)),
+            Register::OFSX | Register::OFSY | Register::OFSZ => Some(ScaleFactor {
+                unit: ScaleUnit::MilliG,
+                per_lsb: 15.6,
+                signed: true,
+            }),
+            Register::DUR => Some(ScaleFactor::unsigned(ScaleUnit::Millisecond, 0.625)),
+            Register::LATENT | Register::WINDOW => {
+                Some(ScaleFactor::unsigned(ScaleUnit::Millisecond, 1.25))
+            }
+            Register::TIME_INACT => Some(ScaleFactor::unsigned(ScaleUnit::Second, 1.0)),
+            Register::TIME_FF => Some(ScaleFactor::unsigned(ScaleUnit::Millisecond, 5.0)),
+            _ => None,
+        }
+    }
+
+    /// Is the register one of the acceleration data registers (`DATAX0`
+    /// through `DATAZ1`)?
+    pub const fn is_data_register(self) -> bool {
+        matches!(
+            self,
+            Register::DATAX0
+                | Register::DATAX1
+                | Register::DATAY0
+                | Register::DATAY1
+                | Register::DATAZ0
+                | Register::DATAZ1
+        )
+    }
+}
+
+impl TryFrom<u8> for Register {
+    type Error = u8;
+
+    /// Get the register at the given address, or return the address as the
+    /// error if no register is mapped there (e.g. the reserved addresses
+    /// 0x01 to 0x1C)
+    fn try_from(addr: u8) -> Result<Register, u8> {
+        match addr {
+            0x00 => Ok(Register::DEVID),
+            0x1D => Ok(Register::THRESH_TAP),
+            0x1E => Ok(Register::OFSX),
+            0x1F => Ok(Register::OFSY),
+            0x20 => Ok(Register::OFSZ),
+            0x21 => Ok(Register::DUR),
+            0x22 => Ok(Register::LATENT),
+            0x23 => Ok(Register::WINDOW),
+            0x24 => Ok(Register::THRESH_ACT),
+            0x25 => Ok(Register::THRESH_INACT),
+            0x26 => Ok(Register::TIME_INACT),
+            0x27 => Ok(Register::ACT_INACT_CTL),
+            0x28 => Ok(Register::THRESH_FF),
+            0x29 => Ok(Register::TIME_FF),
+            0x2A => Ok(Register::TAP_AXES),
+            0x2B => Ok(Register::ACT_TAP_STATUS),
+            0x2C => Ok(Register::BW_RATE),
+            0x2D => Ok(Register::POWER_CTL),
+            0x2E => Ok(Register::INT_ENABLE),
+            0x2F => Ok(Register::INT_MAP),
+            0x30 => Ok(Register::INT_SOURCE),
+            0x31 => Ok(Register::DATA_FORMAT),
+            0x32 => Ok(Register::DATAX0),
+            0x33 => Ok(Register::DATAX1),
+            0x34 => Ok(Register::DATAY0),
+            0x35 => Ok(Register::DATAY1),
+            0x36 => Ok(Register::DATAZ0),
+            0x37 => Ok(Register::DATAZ1),
+            0x38 => Ok(Register::FIFO_CTL),
+            0x39 => Ok(Register::FIFO_STATUS),
+            _ => Err(addr),
+        }
+    }
+}
+
+/// Unit of a register's scale factor
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ScaleUnit {
+    /// Thousandths of standard gravity (mg)
+    MilliG,
+
+    /// Milliseconds
+    Millisecond,
+
+    /// Seconds
+    Second,
+}
+
+/// Documented scale factor of a register, returned by
+/// [`Register::scale_factor`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ScaleFactor {
+    /// Unit of the register value
+    pub unit: ScaleUnit,
+
+    /// Magnitude of one LSB in `unit`
+    pub per_lsb: f32,
+
+    /// The register value is twos complement rather than unsigned
+    pub signed: bool,
+}
+
+impl ScaleFactor {
+    /// Unsigned scale factor
+    const fn unsigned(unit: ScaleUnit, per_lsb: f32) -> ScaleFactor {
+        ScaleFactor {
+            unit,
+            per_lsb,
+            signed: false,
+        }
+    }
+
+    /// Convert a raw register value into `unit`
+    pub fn to_units(self, value: u8) -> f32 {
+        if self.signed {
+            f32::from(value as i8) * self.per_lsb
+        } else {
+            f32::from(value) * self.per_lsb
         }
     }
 }
 
+/// Convert `value` into a count of register LSBs of size `per_lsb`, rounding
+/// to the nearest LSB.
+///
+/// Returns `None` if the result is zero or doesn't fit in an 8-bit register.
+pub(crate) fn nonzero_lsb(value: f32, per_lsb: f32) -> Option<u8> {
+    let lsb = value / per_lsb + 0.5;
+
+    if (1.0..256.0).contains(&lsb) {
+        Some(lsb as u8)
+    } else {
+        None
+    }
+}
+
 bitflags! {
     /// Flags passed as operands to `Register::DATA_FORMAT`
     ///
@@ -289,6 +447,10 @@ bitflags! {
 
         /// "A value of 1 in the SPI bit sets the device to 3-wire SPI mode,
         /// and a value of 0 sets the device to 4-wire SPI mode"
+        ///
+        /// This driver only supports I2C, where the bit has no effect. Note
+        /// that a 3-wire SPI host must set it before any transaction other
+        /// than reading `DEVID`.
         const SPI = 0b01000000;
 
         /// "A value of 0 in the INT_INVERT bit sets the interrupts to active
@@ -315,6 +477,11 @@ bitflags! {
 }
 
 impl DataFormatFlags {
+    /// Get the bus write which stores these flags in `DATA_FORMAT`
+    pub(crate) fn register_write(self) -> [u8; 2] {
+        [Register::DATA_FORMAT.addr(), self.bits()]
+    }
+
     /// Get the [`DataFormatRange`] from the flags
     pub fn range(self) -> DataFormatRange {
         if self.contains(DataFormatFlags::RANGE_HI) {
@@ -329,6 +496,183 @@ impl DataFormatFlags {
             DataFormatRange::PLUSMINUS_2G
         }
     }
+
+    /// Check the `JUSTIFY` bit is set if `left_justified` is, and clear
+    /// otherwise, returning `ErrorKind::Mode` if not (see
+    /// [`crate::Adxl343Error::JustifyMismatch`])
+    #[cfg(any(feature = "i16x3", feature = "u16x3"))]
+    pub(crate) fn check_justify<E: Debug>(self, left_justified: bool) -> Result<(), Error<E>> {
+        if self.contains(DataFormatFlags::JUSTIFY) == left_justified {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::Mode))
+        }
+    }
+
+    /// Get the scale factor of raw readings in g/LSB according to these flags
+    /// (see [`DataFormatRange::lsb_per_g`])
+    #[cfg(feature = "i16x3")]
+    pub(crate) fn g_per_lsb(self) -> f32 {
+        1.0 / self
+            .range()
+            .lsb_per_g(self.contains(DataFormatFlags::FULL_RES))
+    }
+
+    /// Convert a raw reading to ±g according to these flags
+    #[cfg(feature = "i16x3")]
+    pub(crate) fn raw_to_g(self, raw: I16x3) -> F32x3 {
+        let g_per_lsb = self.g_per_lsb();
+
+        F32x3::new(
+            f32::from(raw.x) * g_per_lsb,
+            f32::from(raw.y) * g_per_lsb,
+            f32::from(raw.z) * g_per_lsb,
+        )
+    }
+
+    /// Does any axis of a right-justified raw reading sit at the limit of
+    /// the output range for these flags, meaning the acceleration may have
+    /// exceeded the range and been clipped?
+    #[cfg(feature = "i16x3")]
+    pub(crate) fn is_saturated(self, raw: I16x3) -> bool {
+        let range = self.range();
+        let counts_per_g = range.counts_per_g(self.contains(DataFormatFlags::FULL_RES));
+        let limit = i32::from(counts_per_g) * f32::from(range) as i32;
+        let at_limit = |v: i16| i32::from(v) >= limit - 1 || i32::from(v) <= -limit;
+
+        at_limit(raw.x) || at_limit(raw.y) || at_limit(raw.z)
+    }
+}
+
+bitflags! {
+    /// Interrupt sources, laid out as in `Register::INT_ENABLE`,
+    /// `Register::INT_MAP`, and `Register::INT_SOURCE` (data sheet p.24)
+    pub struct Interrupts: u8 {
+        /// Data ready: new data is available in the data registers
+        const DATA_READY = 0b10000000;
+
+        /// Single tap event
+        const SINGLE_TAP = 0b01000000;
+
+        /// Double tap event
+        const DOUBLE_TAP = 0b00100000;
+
+        /// Acceleration greater than `THRESH_ACT` was detected
+        const ACTIVITY = 0b00010000;
+
+        /// Acceleration less than `THRESH_INACT` for longer than `TIME_INACT`
+        const INACTIVITY = 0b00001000;
+
+        /// Acceleration less than `THRESH_FF` on all axes for longer than `TIME_FF`
+        const FREE_FALL = 0b00000100;
+
+        /// Number of FIFO samples equals or exceeds the watermark
+        const WATERMARK = 0b00000010;
+
+        /// New data has replaced unread data in the FIFO
+        const OVERRUN = 0b00000001;
+    }
+}
+
+impl Default for Interrupts {
+    fn default() -> Self {
+        Interrupts::empty()
+    }
+}
+
+/// Convert a threshold in mg into `THRESH_*` register units of 62.5 mg/LSB,
+/// rounding to the nearest LSB.
+///
+/// Returns `None` if the result doesn't fit in the register (i.e. exceeds
+/// 15.9375g).
+pub(crate) fn mg_to_thresh(mg: u16) -> Option<u8> {
+    let lsb = (u32::from(mg) * 2 + 62) / 125;
+
+    if lsb <= 0xFF {
+        Some(lsb as u8)
+    } else {
+        None
+    }
+}
+
+/// Convert a `THRESH_*` register value (62.5 mg/LSB) into mg
+pub(crate) fn thresh_to_mg(lsb: u8) -> u16 {
+    u16::from(lsb) * 125 / 2
+}
+
+bitflags! {
+    /// Flags passed as operands to `Register::ACT_INACT_CTL` (data sheet p.22)
+    pub struct ActInactCtl: u8 {
+        /// AC-coupled activity detection: acceleration is compared against a
+        /// reference taken at the start of activity detection. When clear,
+        /// detection is DC-coupled and compares against `THRESH_ACT` directly.
+        const ACT_AC = 0b10000000;
+
+        /// Enable activity detection on the X axis
+        const ACT_X = 0b01000000;
+
+        /// Enable activity detection on the Y axis
+        const ACT_Y = 0b00100000;
+
+        /// Enable activity detection on the Z axis
+        const ACT_Z = 0b00010000;
+
+        /// AC-coupled inactivity detection (see `ACT_AC`)
+        const INACT_AC = 0b00001000;
+
+        /// Enable inactivity detection on the X axis
+        const INACT_X = 0b00000100;
+
+        /// Enable inactivity detection on the Y axis
+        const INACT_Y = 0b00000010;
+
+        /// Enable inactivity detection on the Z axis
+        const INACT_Z = 0b00000001;
+    }
+}
+
+impl ActInactCtl {
+    /// All of the activity detection bits
+    pub fn activity() -> ActInactCtl {
+        ActInactCtl::ACT_AC | ActInactCtl::ACT_X | ActInactCtl::ACT_Y | ActInactCtl::ACT_Z
+    }
+
+    /// All of the inactivity detection bits
+    pub fn inactivity() -> ActInactCtl {
+        ActInactCtl::INACT_AC | ActInactCtl::INACT_X | ActInactCtl::INACT_Y | ActInactCtl::INACT_Z
+    }
+}
+
+impl Default for ActInactCtl {
+    fn default() -> Self {
+        ActInactCtl::empty()
+    }
+}
+
+bitflags! {
+    /// Flags passed as operands to `Register::TAP_AXES` (data sheet p.23)
+    pub struct TapAxes: u8 {
+        /// "Setting the suppress bit suppresses double tap detection if
+        /// acceleration greater than the value in THRESH_TAP is present
+        /// between taps."
+        const SUPPRESS = 0b00001000;
+
+        /// Enable tap detection on the X axis
+        const X = 0b00000100;
+
+        /// Enable tap detection on the Y axis
+        const Y = 0b00000010;
+
+        /// Enable tap detection on the Z axis
+        const Z = 0b00000001;
+    }
+}
+
+/// Default `TAP_AXES` settings: tap detection on all axes, without suppress
+impl Default for TapAxes {
+    fn default() -> Self {
+        TapAxes::X | TapAxes::Y | TapAxes::Z
+    }
 }
 
 /// Default `DATA_FORMAT` settings:
@@ -345,6 +689,13 @@ impl Default for DataFormatFlags {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for DataFormatFlags {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "DataFormatFlags({=u8:#b})", self.bits())
+    }
+}
+
 impl From<DataFormatRange> for DataFormatFlags {
     fn from(range: DataFormatRange) -> DataFormatFlags {
         range.bits()
@@ -353,10 +704,12 @@ impl From<DataFormatRange> for DataFormatFlags {
 
 /// g-Range setting flags which can be OR'd with `DataFormatFlags` and passed as
 /// operands to `Register::DATA_FORMAT`
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum DataFormatRange {
-    /// ±2g
+    /// ±2g (default)
+    #[default]
     PLUSMINUS_2G = 0b00,
 
     /// ±4g
@@ -379,6 +732,50 @@ impl DataFormatRange {
             DataFormatRange::PLUSMINUS_16G => DataFormatFlags::RANGE_HI | DataFormatFlags::RANGE_LO,
         }
     }
+
+    /// Get the sensitivity of raw readings in LSB/g at this range.
+    ///
+    /// Uses the typical sensitivities from the data sheet (Table 1, p.4). In
+    /// full resolution mode (`full_res`) the sensitivity is 256 LSB/g
+    /// regardless of range. Otherwise the device is in 10-bit mode, where it
+    /// is 256 LSB/g at ±2g, halving with each doubling of the range.
+    pub fn lsb_per_g(self, full_res: bool) -> f32 {
+        f32::from(self.counts_per_g(full_res))
+    }
+
+    /// Get the sensitivity in LSB/g as an integer (see
+    /// [`DataFormatRange::lsb_per_g`])
+    pub(crate) fn counts_per_g(self, full_res: bool) -> u16 {
+        if full_res {
+            return 256;
+        }
+
+        match self {
+            DataFormatRange::PLUSMINUS_2G => 256,
+            DataFormatRange::PLUSMINUS_4G => 128,
+            DataFormatRange::PLUSMINUS_8G => 64,
+            DataFormatRange::PLUSMINUS_16G => 32,
+        }
+    }
+
+    /// Get the smallest range which can measure accelerations up to ±`g`
+    /// (e.g. 5.0 selects ±8g).
+    ///
+    /// Values beyond 16g saturate to `PLUSMINUS_16G`, the largest range the
+    /// device supports.
+    pub fn for_max_g(g: f32) -> DataFormatRange {
+        let g = if g < 0.0 { -g } else { g };
+
+        if g <= 2.0 {
+            DataFormatRange::PLUSMINUS_2G
+        } else if g <= 4.0 {
+            DataFormatRange::PLUSMINUS_4G
+        } else if g <= 8.0 {
+            DataFormatRange::PLUSMINUS_8G
+        } else {
+            DataFormatRange::PLUSMINUS_16G
+        }
+    }
 }
 
 impl From<DataFormatRange> for f32 {
@@ -391,3 +788,120 @@ impl From<DataFormatRange> for f32 {
         }
     }
 }
+
+/// Data format settings as named fields, convertible to and from
+/// [`DataFormatFlags`], e.g. for loading from an application's configuration
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct DataFormatConfig {
+    /// Apply the self-test force (`SELF_TEST`)
+    pub self_test: bool,
+
+    /// 3-wire rather than 4-wire SPI mode (`SPI`)
+    pub spi_3wire: bool,
+
+    /// Drive the interrupt pins active low (`INT_INVERT`)
+    pub int_active_low: bool,
+
+    /// Full resolution mode (`FULL_RES`)
+    pub full_res: bool,
+
+    /// Left-justified rather than right-justified data (`JUSTIFY`)
+    pub justify: bool,
+
+    /// g range (`RANGE_HI` and `RANGE_LO`)
+    pub range: DataFormatRange,
+}
+
+impl From<DataFormatConfig> for DataFormatFlags {
+    fn from(config: DataFormatConfig) -> DataFormatFlags {
+        let mut flags = config.range.bits();
+        flags.set(DataFormatFlags::SELF_TEST, config.self_test);
+        flags.set(DataFormatFlags::SPI, config.spi_3wire);
+        flags.set(DataFormatFlags::INT_INVERT, config.int_active_low);
+        flags.set(DataFormatFlags::FULL_RES, config.full_res);
+        flags.set(DataFormatFlags::JUSTIFY, config.justify);
+        flags
+    }
+}
+
+impl From<DataFormatFlags> for DataFormatConfig {
+    fn from(flags: DataFormatFlags) -> DataFormatConfig {
+        DataFormatConfig {
+            self_test: flags.contains(DataFormatFlags::SELF_TEST),
+            spi_3wire: flags.contains(DataFormatFlags::SPI),
+            int_active_low: flags.contains(DataFormatFlags::INT_INVERT),
+            full_res: flags.contains(DataFormatFlags::FULL_RES),
+            justify: flags.contains(DataFormatFlags::JUSTIFY),
+            range: flags.range(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mg_to_thresh, DataFormatRange, Register};
+    use core::{convert::TryFrom, iter};
+
+    #[test]
+    fn try_from_round_trips_mapped_addresses() {
+        for addr in iter::once(0x00).chain(0x1D..=0x39) {
+            assert_eq!(Register::try_from(addr).map(Register::addr), Ok(addr));
+        }
+    }
+
+    #[test]
+    fn try_from_rejects_unmapped_addresses() {
+        for addr in (0x01..=0x1C).chain(0x3A..=0xFF) {
+            assert_eq!(Register::try_from(addr), Err(addr));
+        }
+    }
+
+    #[test]
+    fn for_max_g_selects_smallest_range() {
+        assert_eq!(
+            DataFormatRange::for_max_g(0.0),
+            DataFormatRange::PLUSMINUS_2G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(2.0),
+            DataFormatRange::PLUSMINUS_2G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(2.5),
+            DataFormatRange::PLUSMINUS_4G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(-5.0),
+            DataFormatRange::PLUSMINUS_8G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(8.0),
+            DataFormatRange::PLUSMINUS_8G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(16.0),
+            DataFormatRange::PLUSMINUS_16G
+        );
+        assert_eq!(
+            DataFormatRange::for_max_g(100.0),
+            DataFormatRange::PLUSMINUS_16G
+        );
+    }
+
+    #[test]
+    fn mg_to_thresh_rounds_to_nearest_lsb() {
+        assert_eq!(mg_to_thresh(0), Some(0));
+        assert_eq!(mg_to_thresh(31), Some(0));
+        assert_eq!(mg_to_thresh(32), Some(1));
+        assert_eq!(mg_to_thresh(1000), Some(16));
+        assert_eq!(mg_to_thresh(15937), Some(0xFF));
+        assert_eq!(mg_to_thresh(15968), Some(0xFF));
+    }
+
+    #[test]
+    fn mg_to_thresh_rejects_values_beyond_register() {
+        assert_eq!(mg_to_thresh(15969), None);
+        assert_eq!(mg_to_thresh(16000), None);
+        assert_eq!(mg_to_thresh(u16::MAX), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Multi-sample reading helpers
+
+use crate::Adxl343;
+use accelerometer::{
+    vector::{F32x3, I16x3},
+    Error, ErrorKind, RawAccelerometer,
+};
+use core::{fmt::Debug, iter};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Get the average of `n` consecutive normalized (±g) readings.
+    ///
+    /// Waits for `DATA_READY` before each reading so that `n` distinct samples
+    /// are averaged rather than the same sample being read repeatedly.
+    /// Polling `DATA_READY` reads `INT_SOURCE`, which clears any latched
+    /// tap, activity, inactivity, and free-fall interrupts.
+    ///
+    /// Samples are accumulated as described for [`Adxl343::accel_norm_mean`].
+    ///
+    /// Returns `ErrorKind::Param` if `n` is zero.
+    pub fn accel_norm_averaged<D>(&mut self, n: u8, delay: &mut D) -> Result<F32x3, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.average_samples(usize::from(n), |adxl343| {
+            adxl343.accel_raw_when_ready(delay)
+        })
+    }
+
+    /// Get the mean of `n` normalized (±g) readings taken back-to-back,
+    /// without waiting for `DATA_READY`.
+    ///
+    /// Readings are taken as fast as the bus allows, so consecutive readings
+    /// may repeat the same sample if the output data rate is slower than the
+    /// bus: use [`Adxl343::accel_norm_averaged`] to average distinct samples.
+    ///
+    /// Raw counts are summed in an `i64`, so the sum can't overflow for any
+    /// practical `n`, and are only scaled to g once, after averaging.
+    ///
+    /// Transient bus errors are tolerated by the driver's transaction
+    /// retries (see [`Adxl343::set_retries`]): a failed read of the data
+    /// registers is repeated, and an error which outlasts the retries is
+    /// returned.
+    ///
+    /// Returns `ErrorKind::Param` if `n` is zero.
+    pub fn accel_norm_mean(&mut self, n: usize) -> Result<F32x3, Error<E>> {
+        self.average_samples(n, |adxl343| adxl343.accel_raw())
+    }
+
+    /// Average `n` raw readings taken with `read` and scale the result to g
+    fn average_samples<F>(&mut self, n: usize, mut read: F) -> Result<F32x3, Error<E>>
+    where
+        F: FnMut(&mut Self) -> Result<I16x3, Error<E>>,
+    {
+        if n == 0 {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let mut sum = [0i64; 3];
+
+        for _ in 0..n {
+            let sample = read(self)?;
+            sum[0] += i64::from(sample.x);
+            sum[1] += i64::from(sample.y);
+            sum[2] += i64::from(sample.z);
+        }
+
+        let scale = self.data_format.g_per_lsb() / n as f32;
+
+        Ok(F32x3::new(
+            sum[0] as f32 * scale,
+            sum[1] as f32 * scale,
+            sum[2] as f32 * scale,
+        ))
+    }
+
+    /// Iterate over raw acceleration readings, performing one read per call
+    /// to `next()`.
+    ///
+    /// The iterator never ends, so use combinators such as `take` to capture
+    /// a fixed number of samples. Readings are taken as fast as the iterator
+    /// is driven, so the same sample may be returned more than once if it
+    /// is driven faster than the output data rate: see
+    /// [`Adxl343::samples_when_ready`].
+    pub fn samples(&mut self) -> impl Iterator<Item = Result<I16x3, Error<E>>> + '_ {
+        iter::from_fn(move || Some(self.accel_raw()))
+    }
+
+    /// Iterate over raw acceleration readings, waiting for `DATA_READY`
+    /// before each read so every item is a new sample (see
+    /// [`Adxl343::accel_raw_when_ready`]).
+    pub fn samples_when_ready<'a, D>(
+        &'a mut self,
+        delay: &'a mut D,
+    ) -> impl Iterator<Item = Result<I16x3, Error<E>>> + 'a
+    where
+        D: DelayNs,
+    {
+        iter::from_fn(move || Some(self.accel_raw_when_ready(delay)))
+    }
+
+    /// Read `count` normalized (±g) samples, passing each to `on_sample` as
+    /// it arrives.
+    ///
+    /// Waits for `DATA_READY` before each reading (see
+    /// [`Adxl343::accel_raw_when_ready`]), so samples are delivered at the
+    /// output data rate and none is delivered twice. Returns the first error
+    /// encountered, after which no further samples are read.
+    pub fn stream_norm<D, F>(
+        &mut self,
+        count: usize,
+        delay: &mut D,
+        mut on_sample: F,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+        F: FnMut(F32x3),
+    {
+        for _ in 0..count {
+            let raw = self.accel_raw_when_ready(delay)?;
+            on_sample(self.data_format.raw_to_g(raw));
+        }
+
+        Ok(())
+    }
+
+    /// Read samples into `buf` until `pred` returns `true` for a sample,
+    /// returning the index in `buf` of that sample.
+    ///
+    /// `buf` is used as a ring buffer: once it is full, each new sample
+    /// overwrites the oldest, so on return it holds the matching sample and
+    /// up to `buf.len() - 1` samples preceding it. The oldest of these is at
+    /// the index following the returned one (wrapping around), unless fewer
+    /// than `buf.len()` samples were read.
+    ///
+    /// Readings are taken back to back, as for [`Adxl343::samples`], so the
+    /// same sample may be read more than once. Waits indefinitely for `pred`
+    /// to match, and returns `ErrorKind::Param` if `buf` is empty.
+    pub fn sample_until<F>(&mut self, buf: &mut [I16x3], mut pred: F) -> Result<usize, Error<E>>
+    where
+        F: FnMut(I16x3) -> bool,
+    {
+        if buf.is_empty() {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let mut index = 0;
+
+        loop {
+            let sample = self.accel_raw()?;
+            buf[index] = sample;
+
+            if pred(sample) {
+                return Ok(index);
+            }
+
+            index = (index + 1) % buf.len();
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Self-test support
+//!
+//! See "Self-Test" in the ADXL343 data sheet (p.30):
+//! <https://www.analog.com/media/en/technical-documentation/data-sheets/adxl343.pdf>
+
+use crate::{
+    register::{DataFormatFlags, DataFormatRange},
+    Adxl343,
+};
+use accelerometer::{
+    vector::{F32x3, I16x3},
+    Error, ErrorKind, RawAccelerometer,
+};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Scale factor in g/LSB of full resolution mode
+const FULL_RES_G_PER_LSB: f32 = 0.0039;
+
+/// Self-test output change limits in LSB for full resolution mode, from the
+/// data sheet's self-test specifications (at VS = 2.5 V)
+const SELF_TEST_LIMITS: [(i16, i16); 3] = [(50, 540), (-540, -50), (75, 875)];
+
+/// Number of samples averaged with the self-test force off and on
+const SELF_TEST_SAMPLES: u8 = 10;
+
+/// Number of samples discarded after toggling the self-test force to
+/// allow the output to settle
+const SELF_TEST_SETTLE_SAMPLES: u8 = 4;
+
+/// Result of [`Adxl343::self_test`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SelfTestResult {
+    /// Change in output caused by the self-test force, in g
+    pub delta: F32x3,
+
+    /// X axis response is within the data sheet limits
+    pub x_pass: bool,
+
+    /// Y axis response is within the data sheet limits
+    pub y_pass: bool,
+
+    /// Z axis response is within the data sheet limits
+    pub z_pass: bool,
+}
+
+impl SelfTestResult {
+    /// Did all axes pass?
+    pub fn passed(&self) -> bool {
+        self.x_pass && self.y_pass && self.z_pass
+    }
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Run the self-test and check the response on each axis against the
+    /// minimum and maximum output change given in the data sheet.
+    ///
+    /// The output is sampled with the self-test force off and then on, after
+    /// allowing it to settle, and the averages compared. To apply the data
+    /// sheet limits independently of the configured range, the test runs in
+    /// full resolution mode; the data format is restored before returning.
+    /// The device must be measuring and stationary.
+    ///
+    /// The limits are specified for a 2.5 V supply. The self-test response
+    /// grows with supply voltage, so at higher supply voltages a healthy part
+    /// may exceed the upper limit: see the data sheet for scaling factors.
+    pub fn self_test<D>(&mut self, delay: &mut D) -> Result<SelfTestResult, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let delta = self.self_test_signature(delay)?;
+        let pass = |axis: usize| {
+            let (min, max) = SELF_TEST_LIMITS[axis];
+            delta[axis] >= min && delta[axis] <= max
+        };
+
+        Ok(SelfTestResult {
+            delta: F32x3::new(
+                f32::from(delta[0]) * FULL_RES_G_PER_LSB,
+                f32::from(delta[1]) * FULL_RES_G_PER_LSB,
+                f32::from(delta[2]) * FULL_RES_G_PER_LSB,
+            ),
+            x_pass: pass(0),
+            y_pass: pass(1),
+            z_pass: pass(2),
+        })
+    }
+
+    /// Run the self-test and store its response as the baseline used by
+    /// [`Adxl343::self_test_drift`], returning the response in g.
+    pub fn capture_self_test_baseline<D>(&mut self, delay: &mut D) -> Result<F32x3, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let response = self.self_test_response(delay)?;
+        self.self_test_baseline = Some(response);
+        Ok(response)
+    }
+
+    /// Get the stored self-test baseline, if any
+    pub fn self_test_baseline(&self) -> Option<F32x3> {
+        self.self_test_baseline
+    }
+
+    /// Set the self-test baseline, e.g. to restore one captured at
+    /// manufacturing time from persistent storage
+    pub fn set_self_test_baseline(&mut self, baseline: F32x3) {
+        self.self_test_baseline = Some(baseline);
+    }
+
+    /// Run the self-test and return the difference in g between its response
+    /// and the stored baseline. A growing drift over the sensor's lifetime
+    /// can indicate degradation.
+    ///
+    /// Returns `ErrorKind::Mode` if no baseline has been captured or set.
+    pub fn self_test_drift<D>(&mut self, delay: &mut D) -> Result<F32x3, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let baseline = self
+            .self_test_baseline
+            .ok_or_else(|| Error::new(ErrorKind::Mode))?;
+
+        let response = self.self_test_response(delay)?;
+
+        Ok(F32x3::new(
+            response.x - baseline.x,
+            response.y - baseline.y,
+            response.z - baseline.z,
+        ))
+    }
+
+    /// Run the self-test in full resolution ±16g mode (a fixed 3.9 mg/LSB
+    /// scale factor) and return the raw per-axis change in output caused by
+    /// the self-test force.
+    ///
+    /// The self-test response varies from unit to unit, so this can be stored
+    /// at manufacturing time and compared at boot to detect a swapped sensor.
+    /// The data format is restored before returning.
+    pub fn self_test_signature<D>(&mut self, delay: &mut D) -> Result<[i16; 3], Error<E>>
+    where
+        D: DelayNs,
+    {
+        let data_format = self.data_format;
+        self.data_format(DataFormatFlags::FULL_RES | DataFormatRange::PLUSMINUS_16G.bits())?;
+        let delta = self.self_test_delta(delay);
+        self.data_format(data_format)?;
+        delta
+    }
+
+    /// Measure the change in output (in g) caused by applying the self-test
+    /// force, using [`Adxl343::self_test_signature`] so that responses are
+    /// comparable regardless of the configured range. The device must be
+    /// measuring.
+    pub(crate) fn self_test_response<D>(&mut self, delay: &mut D) -> Result<F32x3, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let [x, y, z] = self.self_test_signature(delay)?;
+
+        Ok(F32x3::new(
+            f32::from(x) * FULL_RES_G_PER_LSB,
+            f32::from(y) * FULL_RES_G_PER_LSB,
+            f32::from(z) * FULL_RES_G_PER_LSB,
+        ))
+    }
+
+    /// Measure the average change in raw output caused by applying the
+    /// self-test force, with the current data format.
+    ///
+    /// The `SELF_TEST` bit is always cleared before returning, even if
+    /// sampling fails.
+    fn self_test_delta<D>(&mut self, delay: &mut D) -> Result<[i16; 3], Error<E>>
+    where
+        D: DelayNs,
+    {
+        let data_format = self.data_format;
+        let off = self.self_test_sum(delay)?;
+
+        self.data_format(data_format | DataFormatFlags::SELF_TEST)?;
+        let on = self.self_test_sum(delay);
+        self.data_format(data_format)?;
+        let on = on?;
+
+        let n = i32::from(SELF_TEST_SAMPLES);
+        Ok([
+            ((on[0] - off[0]) / n) as i16,
+            ((on[1] - off[1]) / n) as i16,
+            ((on[2] - off[2]) / n) as i16,
+        ])
+    }
+
+    /// Discard samples while the output settles, then sum fresh raw samples
+    fn self_test_sum<D>(&mut self, delay: &mut D) -> Result<[i32; 3], Error<E>>
+    where
+        D: DelayNs,
+    {
+        for _ in 0..SELF_TEST_SETTLE_SAMPLES {
+            self.wait_for_data_ready(delay)?;
+            let _: I16x3 = self.accel_raw()?;
+        }
+
+        let mut sum = [0i32; 3];
+
+        for _ in 0..SELF_TEST_SAMPLES {
+            self.wait_for_data_ready(delay)?;
+            let sample: I16x3 = self.accel_raw()?;
+            sum[0] += i32::from(sample.x);
+            sum[1] += i32::from(sample.y);
+            sum[2] += i32::from(sample.z);
+        }
+
+        Ok(sum)
+    }
+}
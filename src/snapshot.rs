@@ -0,0 +1,154 @@
+//! Snapshot of the complete device configuration
+
+use crate::{
+    power::{AUTO_SLEEP, LINK, MEASURE, SLEEP},
+    rate::LOW_POWER,
+    register::{DataFormatFlags, DataFormatRange, Register},
+    ActivityConfig, Adxl343, DataRate, FifoConfig, FreeFallConfig, InterruptConfig, TapConfig,
+    TapStatus, WakeupRate,
+};
+use accelerometer::Error;
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Device configuration as read from its registers, returned by
+/// `Adxl343::dump_config`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ConfigSnapshot {
+    /// Output data rate (`BW_RATE` rate code)
+    pub data_rate: DataRate,
+
+    /// Low power mode (`BW_RATE` `LOW_POWER` bit)
+    pub low_power: bool,
+
+    /// Measurement mode, as opposed to standby (`POWER_CTL` Measure bit)
+    pub measuring: bool,
+
+    /// Sleep mode (`POWER_CTL` Sleep bit)
+    pub sleep: bool,
+
+    /// Automatic sleep on inactivity (`POWER_CTL` AUTO_SLEEP bit)
+    pub auto_sleep: bool,
+
+    /// Activity and inactivity detection are linked (`POWER_CTL` Link bit)
+    pub link: bool,
+
+    /// Frequency of readings in sleep mode (`POWER_CTL` Wakeup bits)
+    pub wakeup_rate: WakeupRate,
+
+    /// Data format (`DATA_FORMAT`)
+    pub data_format: DataFormatFlags,
+
+    /// Interrupt enables, mapping, and polarity (`INT_ENABLE`, `INT_MAP`,
+    /// and the `DATA_FORMAT` `INT_INVERT` bit)
+    pub interrupts: InterruptConfig,
+
+    /// FIFO configuration (`FIFO_CTL`)
+    pub fifo: FifoConfig,
+
+    /// Tap detection configuration
+    pub tap: TapConfig,
+
+    /// Activity and inactivity detection configuration
+    pub activity: ActivityConfig,
+
+    /// Free-fall detection configuration
+    pub free_fall: FreeFallConfig,
+}
+
+/// Summary of the device's power state, output data rate, data format, and
+/// FIFO fill level, returned by `Adxl343::status`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DeviceStatus {
+    /// Measurement mode, as opposed to standby (`POWER_CTL` Measure bit)
+    pub measuring: bool,
+
+    /// Sleep mode (`POWER_CTL` Sleep bit)
+    pub sleep: bool,
+
+    /// The device is asleep, either in sleep mode or after inactivity with
+    /// auto-sleep enabled (`ACT_TAP_STATUS` Asleep bit)
+    pub asleep: bool,
+
+    /// Output data rate (`BW_RATE` rate code)
+    pub data_rate: DataRate,
+
+    /// Low power mode (`BW_RATE` `LOW_POWER` bit)
+    pub low_power: bool,
+
+    /// g range (`DATA_FORMAT` range bits)
+    pub range: DataFormatRange,
+
+    /// Full resolution mode (`DATA_FORMAT` `FULL_RES` bit)
+    pub full_resolution: bool,
+
+    /// Number of samples available in the FIFO (`FIFO_STATUS` Entries bits)
+    pub fifo_entries: u8,
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Read the complete device configuration into a [`ConfigSnapshot`],
+    /// e.g. for logging or comparing against the expected configuration.
+    ///
+    /// Only configuration registers are read, so this doesn't clear latched
+    /// interrupts or disturb the FIFO, and the driver's cached state is left
+    /// unchanged.
+    pub fn dump_config(&mut self) -> Result<ConfigSnapshot, Error<E>> {
+        let bw_rate = self.read_register(Register::BW_RATE)?;
+        let power_ctl = self.read_register(Register::POWER_CTL)?;
+        let data_format =
+            DataFormatFlags::from_bits_truncate(self.read_register(Register::DATA_FORMAT)?);
+
+        Ok(ConfigSnapshot {
+            data_rate: DataRate::from_code(bw_rate),
+            low_power: bw_rate & LOW_POWER != 0,
+            measuring: power_ctl & MEASURE != 0,
+            sleep: power_ctl & SLEEP != 0,
+            auto_sleep: power_ctl & AUTO_SLEEP != 0,
+            link: power_ctl & LINK != 0,
+            wakeup_rate: WakeupRate::from_bits(power_ctl),
+            data_format,
+            interrupts: InterruptConfig {
+                enabled: self.interrupts()?,
+                int2_routed: self.interrupt_map()?,
+                active_low: data_format.contains(DataFormatFlags::INT_INVERT),
+            },
+            fifo: self.fifo_config()?,
+            tap: self.tap_config()?,
+            activity: self.activity_config()?,
+            free_fall: self.free_fall_config()?,
+        })
+    }
+
+    /// Read a summary of the device's state into a [`DeviceStatus`], e.g. for
+    /// periodic health reporting.
+    ///
+    /// Reads `ACT_TAP_STATUS`, `BW_RATE`, and `POWER_CTL` in a single
+    /// transaction, then `DATA_FORMAT` and `FIFO_STATUS`. None of these reads
+    /// clear latched interrupts or disturb the FIFO, and the range and
+    /// resolution are read from the device rather than the cached data
+    /// format.
+    pub fn status(&mut self) -> Result<DeviceStatus, Error<E>> {
+        // ACT_TAP_STATUS, BW_RATE, POWER_CTL
+        let mut block = [0u8; 3];
+        self.read_registers(Register::ACT_TAP_STATUS, &mut block)?;
+        let (bw_rate, power_ctl) = (block[1], block[2]);
+        let data_format = self.read_data_format()?;
+
+        Ok(DeviceStatus {
+            measuring: power_ctl & MEASURE != 0,
+            sleep: power_ctl & SLEEP != 0,
+            asleep: TapStatus::from(block[0]).asleep,
+            data_rate: DataRate::from_code(bw_rate),
+            low_power: bw_rate & LOW_POWER != 0,
+            range: data_format.range(),
+            full_resolution: data_format.contains(DataFormatFlags::FULL_RES),
+            fifo_entries: self.fifo_status()?.entries,
+        })
+    }
+}
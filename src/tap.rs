@@ -0,0 +1,338 @@
+//! Tap detection
+
+use crate::{
+    register::{mg_to_thresh, nonzero_lsb, Interrupts, Register, TapAxes},
+    Adxl343, IntPin,
+};
+use accelerometer::{Error, ErrorKind};
+use core::fmt::Debug;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+/// Tap detection configuration, applied with `Adxl343::configure_tap`.
+///
+/// Values are in register units; see the documentation for each register in
+/// the data sheet (p.21-23) for details.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TapConfig {
+    /// Tap threshold (`THRESH_TAP`): 62.5 mg/LSB
+    pub threshold: u8,
+
+    /// Maximum tap duration (`DUR`): 625 µs/LSB
+    pub duration: u8,
+
+    /// Wait from the first tap until the double tap window opens (`LATENT`):
+    /// 1.25 ms/LSB. Ignored unless `double_tap` is set.
+    pub latency: u8,
+
+    /// Time in which a second tap can begin (`WINDOW`): 1.25 ms/LSB.
+    /// Ignored unless `double_tap` is set.
+    pub window: u8,
+
+    /// Axes which participate in tap detection (`TAP_AXES`)
+    pub axes: TapAxes,
+
+    /// Enable double tap detection in addition to single tap
+    pub double_tap: bool,
+}
+
+/// Default tap configuration:
+///
+/// - Threshold: 1.25g
+/// - Duration: 31.25ms
+/// - All axes
+/// - Single tap only
+impl Default for TapConfig {
+    fn default() -> Self {
+        TapConfig {
+            threshold: 20,
+            duration: 50,
+            latency: 0,
+            window: 0,
+            axes: TapAxes::default(),
+            double_tap: false,
+        }
+    }
+}
+
+impl TapConfig {
+    /// Get the values to write to `THRESH_TAP`, `DUR`, `LATENT`, `WINDOW`,
+    /// and `TAP_AXES`, in that order, with `LATENT` and `WINDOW` zeroed if
+    /// `double_tap` is unset
+    pub(crate) fn registers(self) -> [(Register, u8); 5] {
+        let (latency, window) = if self.double_tap {
+            (self.latency, self.window)
+        } else {
+            (0, 0)
+        };
+
+        [
+            (Register::THRESH_TAP, self.threshold),
+            (Register::DUR, self.duration),
+            (Register::LATENT, latency),
+            (Register::WINDOW, window),
+            (Register::TAP_AXES, self.axes.bits()),
+        ]
+    }
+}
+
+/// Double tap detection configuration, applied with
+/// `Adxl343::enable_double_tap`.
+///
+/// Values are in register units, as for [`TapConfig`]. All of them must be
+/// nonzero.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DoubleTapConfig {
+    /// Tap threshold (`THRESH_TAP`): 62.5 mg/LSB
+    pub threshold: u8,
+
+    /// Maximum tap duration (`DUR`): 625 µs/LSB
+    pub duration: u8,
+
+    /// Wait from the first tap until the double tap window opens (`LATENT`):
+    /// 1.25 ms/LSB
+    pub latency: u8,
+
+    /// Time in which a second tap can begin (`WINDOW`): 1.25 ms/LSB
+    pub window: u8,
+
+    /// Axes which participate in tap detection, and whether double taps are
+    /// suppressed by excessive motion between taps (`TAP_AXES`)
+    pub axes: TapAxes,
+}
+
+/// Default double tap configuration:
+///
+/// - Threshold: 3g
+/// - Duration: 20ms
+/// - Latency: 100ms
+/// - Window: 300ms
+/// - All axes, with suppression
+impl Default for DoubleTapConfig {
+    fn default() -> Self {
+        DoubleTapConfig {
+            threshold: 48,
+            duration: 32,
+            latency: 80,
+            window: 240,
+            axes: TapAxes::default() | TapAxes::SUPPRESS,
+        }
+    }
+}
+
+/// Decoded contents of the `ACT_TAP_STATUS` register (data sheet p.23),
+/// returned by `Adxl343::tap_status`.
+///
+/// The activity and tap source bits indicate the first axis involved in the
+/// most recent activity or tap event, and are cleared when the next event
+/// of that type occurs.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TapStatus {
+    /// X axis involved in an activity event (`ACT_X source`)
+    pub activity_x: bool,
+
+    /// Y axis involved in an activity event (`ACT_Y source`)
+    pub activity_y: bool,
+
+    /// Z axis involved in an activity event (`ACT_Z source`)
+    pub activity_z: bool,
+
+    /// The device is asleep (`Asleep`)
+    pub asleep: bool,
+
+    /// X axis involved in a tap event (`TAP_X source`)
+    pub tap_x: bool,
+
+    /// Y axis involved in a tap event (`TAP_Y source`)
+    pub tap_y: bool,
+
+    /// Z axis involved in a tap event (`TAP_Z source`)
+    pub tap_z: bool,
+}
+
+impl From<u8> for TapStatus {
+    fn from(bits: u8) -> TapStatus {
+        TapStatus {
+            activity_x: bits & 0b0100_0000 != 0,
+            activity_y: bits & 0b0010_0000 != 0,
+            activity_z: bits & 0b0001_0000 != 0,
+            asleep: bits & 0b0000_1000 != 0,
+            tap_x: bits & 0b0000_0100 != 0,
+            tap_y: bits & 0b0000_0010 != 0,
+            tap_z: bits & 0b0000_0001 != 0,
+        }
+    }
+}
+
+impl<I2C, DELAY, E> Adxl343<I2C, DELAY>
+where
+    I2C: I2c<Error = E>,
+    DELAY: DelayNs,
+    E: Debug,
+{
+    /// Configure single tap detection on all axes.
+    ///
+    /// - `threshold_g`: minimum tap magnitude, programmed into `THRESH_TAP`
+    ///   at 62.5 mg/LSB (up to 15.9375g)
+    /// - `max_duration_ms`: maximum time the tap may stay above the threshold,
+    ///   programmed into `DUR` at 625 µs/LSB (up to 159.375ms)
+    ///
+    /// The data sheet warns that zero values for either register result in
+    /// undesirable behavior or disable tap detection, so values which round
+    /// to zero (or don't fit in the register) return `ErrorKind::Param`.
+    ///
+    /// Double tap detection is disabled by zeroing `LATENT` and `WINDOW`.
+    pub fn set_single_tap(
+        &mut self,
+        threshold_g: f32,
+        max_duration_ms: f32,
+    ) -> Result<(), Error<E>> {
+        let threshold =
+            nonzero_lsb(threshold_g, 0.0625).ok_or_else(|| Error::new(ErrorKind::Param))?;
+        let duration =
+            nonzero_lsb(max_duration_ms, 0.625).ok_or_else(|| Error::new(ErrorKind::Param))?;
+
+        self.write_register(Register::THRESH_TAP, threshold)?;
+        self.write_register(Register::DUR, duration)?;
+        self.write_register(Register::LATENT, 0)?;
+        self.write_register(Register::WINDOW, 0)?;
+        self.write_register(Register::TAP_AXES, TapAxes::default().bits())
+    }
+
+    /// Configure tap detection, writing `THRESH_TAP`, `DUR`, `LATENT`,
+    /// `WINDOW`, and `TAP_AXES`.
+    ///
+    /// If `double_tap` is unset, `LATENT` and `WINDOW` are written as zero to
+    /// disable double tap detection. If it is set, they must both be nonzero,
+    /// otherwise `ErrorKind::Param` is returned.
+    pub fn configure_tap(&mut self, config: TapConfig) -> Result<(), Error<E>> {
+        if config.double_tap && (config.latency == 0 || config.window == 0) {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        for (register, value) in config.registers() {
+            self.write_register(register, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Select the axes which participate in tap detection, and whether
+    /// double taps are suppressed by excessive motion between taps, by
+    /// writing `TAP_AXES`.
+    ///
+    /// Setting `suppress` helps avoid false double taps on a vibrating
+    /// chassis.
+    pub fn set_tap_axes(
+        &mut self,
+        x: bool,
+        y: bool,
+        z: bool,
+        suppress: bool,
+    ) -> Result<(), Error<E>> {
+        let mut axes = TapAxes::empty();
+        axes.set(TapAxes::X, x);
+        axes.set(TapAxes::Y, y);
+        axes.set(TapAxes::Z, z);
+        axes.set(TapAxes::SUPPRESS, suppress);
+        self.write_register(Register::TAP_AXES, axes.bits())
+    }
+
+    /// Get the tap detection axes and suppress bit by reading `TAP_AXES`
+    pub fn tap_axes(&mut self) -> Result<TapAxes, Error<E>> {
+        Ok(TapAxes::from_bits_truncate(
+            self.read_register(Register::TAP_AXES)?,
+        ))
+    }
+
+    /// Read the tap detection configuration from `THRESH_TAP`, `DUR`,
+    /// `LATENT`, `WINDOW`, and `TAP_AXES`.
+    ///
+    /// `double_tap` is reported as enabled if `LATENT` and `WINDOW` are both
+    /// nonzero.
+    pub fn tap_config(&mut self) -> Result<TapConfig, Error<E>> {
+        // THRESH_TAP, OFSX, OFSY, OFSZ, DUR, LATENT, WINDOW
+        let mut block = [0u8; 7];
+        self.read_registers(Register::THRESH_TAP, &mut block)?;
+        let (latency, window) = (block[5], block[6]);
+
+        Ok(TapConfig {
+            threshold: block[0],
+            duration: block[4],
+            latency,
+            window,
+            axes: self.tap_axes()?,
+            double_tap: latency != 0 && window != 0,
+        })
+    }
+
+    /// Read and decode the `ACT_TAP_STATUS` register to find which axes were
+    /// involved in the most recent tap and activity events
+    pub fn tap_status(&mut self) -> Result<TapStatus, Error<E>> {
+        Ok(self.read_register(Register::ACT_TAP_STATUS)?.into())
+    }
+
+    /// Set the tap threshold (`THRESH_TAP`) in mg.
+    ///
+    /// Rounded to the nearest 62.5 mg LSB. Thresholds above 15.9375g don't
+    /// fit in the register and return `ErrorKind::Param`.
+    pub fn set_tap_threshold_mg(&mut self, threshold_mg: u16) -> Result<(), Error<E>> {
+        let threshold = mg_to_thresh(threshold_mg).ok_or_else(|| Error::new(ErrorKind::Param))?;
+        self.write_register(Register::THRESH_TAP, threshold)
+    }
+
+    /// Enable double tap detection, raising the `DOUBLE_TAP` interrupt on
+    /// `int_pin`.
+    ///
+    /// Disables the `DOUBLE_TAP` interrupt while the tap registers and
+    /// `INT_MAP` are written, then enables it. Other interrupt sources are
+    /// left unchanged.
+    ///
+    /// Returns `ErrorKind::Param` without accessing the bus if any of the
+    /// configuration values is zero, as a zero `LATENT` or `WINDOW` disables
+    /// double tap detection and the data sheet warns against a zero
+    /// threshold or duration.
+    pub fn enable_double_tap(
+        &mut self,
+        config: DoubleTapConfig,
+        int_pin: IntPin,
+    ) -> Result<(), Error<E>> {
+        let values = [
+            config.threshold,
+            config.duration,
+            config.latency,
+            config.window,
+        ];
+
+        if values.contains(&0) {
+            return Err(Error::new(ErrorKind::Param));
+        }
+
+        let enabled = self.interrupts()?;
+        self.set_interrupts(enabled - Interrupts::DOUBLE_TAP)?;
+
+        self.configure_tap(TapConfig {
+            threshold: config.threshold,
+            duration: config.duration,
+            latency: config.latency,
+            window: config.window,
+            axes: config.axes,
+            double_tap: true,
+        })?;
+
+        self.route_interrupts(Interrupts::DOUBLE_TAP, int_pin)?;
+        self.set_interrupts(enabled | Interrupts::DOUBLE_TAP)
+    }
+
+    /// Disable double tap detection enabled by
+    /// [`Adxl343::enable_double_tap`], by disabling the `DOUBLE_TAP`
+    /// interrupt and zeroing `LATENT` and `WINDOW`.
+    ///
+    /// The tap threshold, duration, and axes are left as they are, so single
+    /// tap detection continues to work if enabled.
+    pub fn disable_double_tap(&mut self) -> Result<(), Error<E>> {
+        let enabled = self.interrupts()?;
+        self.set_interrupts(enabled - Interrupts::DOUBLE_TAP)?;
+        self.write_register(Register::LATENT, 0)?;
+        self.write_register(Register::WINDOW, 0)
+    }
+}
@@ -0,0 +1,510 @@
+//! Bus transaction tests using `embedded-hal-mock`
+
+use accelerometer::ErrorKind;
+use adxl343::{
+    Adxl343, Adxl343Builder, DataFormatFlags, DoubleTapConfig, IntPin, InterruptConfig, Interrupts,
+    Register, ADDRESS, DEVICE_ID,
+};
+use embedded_hal::i2c::ErrorKind as I2cErrorKind;
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction};
+
+#[cfg(feature = "u16x3")]
+use accelerometer::vector::U16x3;
+#[cfg(any(feature = "i16x3", feature = "u16x3"))]
+use accelerometer::RawAccelerometer;
+#[cfg(feature = "i16x3")]
+use accelerometer::{
+    vector::{F32x3, I16x3},
+    Accelerometer,
+};
+#[cfg(feature = "i16x3")]
+use adxl343::{Adxl343Error, DataRate, Endian};
+
+/// Transactions performed by `Adxl343::new_with_data_format`, with `DEVID`
+/// returning the ADXL343 device ID and the given data format
+fn init_transactions(data_format: DataFormatFlags) -> Vec<Transaction> {
+    let mut transactions = vec![Transaction::write_read(
+        ADDRESS,
+        vec![Register::DEVID.addr()],
+        vec![DEVICE_ID],
+    )];
+    transactions.extend(configure_transactions(data_format));
+    transactions
+}
+
+/// Transactions performed by the initial configuration, after the device ID
+/// check
+fn configure_transactions(data_format: DataFormatFlags) -> Vec<Transaction> {
+    vec![
+        Transaction::write(
+            ADDRESS,
+            vec![Register::DATA_FORMAT.addr(), data_format.bits()],
+        ),
+        Transaction::write_read(ADDRESS, vec![Register::BW_RATE.addr()], vec![0x0A]),
+        Transaction::write(ADDRESS, vec![Register::INT_ENABLE.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::THRESH_TAP.addr(), 20]),
+        Transaction::write(ADDRESS, vec![Register::DUR.addr(), 50]),
+        Transaction::write(ADDRESS, vec![Register::LATENT.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::WINDOW.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::TAP_AXES.addr(), 0b0000_0111]),
+        Transaction::write(ADDRESS, vec![Register::POWER_CTL.addr(), 0x08]),
+    ]
+}
+
+/// Transaction reading the given raw reading from the data registers, which
+/// are read in a single burst, least significant byte first
+#[cfg(feature = "i16x3")]
+fn data_transaction(x: i16, y: i16, z: i16) -> Transaction {
+    let [x0, x1] = x.to_le_bytes();
+    let [y0, y1] = y.to_le_bytes();
+    let [z0, z1] = z.to_le_bytes();
+    data_bytes_transaction([x0, x1, y0, y1, z0, z1])
+}
+
+/// Transaction reading the given bytes from the data registers in a single
+/// burst
+#[cfg(any(feature = "i16x3", feature = "u16x3"))]
+fn data_bytes_transaction(bytes: [u8; 6]) -> Transaction {
+    Transaction::write_read(ADDRESS, vec![Register::DATAX0.addr()], bytes.to_vec())
+}
+
+/// Assert that each axis of a reading in g is within 1 mg of the expected
+/// value
+#[cfg(feature = "i16x3")]
+fn assert_g(g: F32x3, expected: [f32; 3]) {
+    for (actual, expected) in [g.x, g.y, g.z].iter().zip(expected.iter()) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {}g, got {}g",
+            expected,
+            actual
+        );
+    }
+}
+
+#[test]
+fn new_checks_device_id_and_initializes() {
+    let expectations = init_transactions(DataFormatFlags::default());
+    let mut i2c = I2cMock::new(&expectations);
+
+    Adxl343::new(i2c.clone()).unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn new_rejects_unknown_device_id() {
+    let expectations = [Transaction::write_read(
+        ADDRESS,
+        vec![Register::DEVID.addr()],
+        vec![0x00],
+    )];
+    let mut i2c = I2cMock::new(&expectations);
+
+    let err = Adxl343::new(i2c.clone()).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Device);
+
+    i2c.done();
+}
+
+#[test]
+fn write_register_frames_address_and_value() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.push(Transaction::write(
+        ADDRESS,
+        vec![Register::THRESH_ACT.addr(), 0x10],
+    ));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    adxl343.write_register(Register::THRESH_ACT, 0x10).unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn write_register_rejects_data_format_and_read_only_registers() {
+    let expectations = init_transactions(DataFormatFlags::default());
+    let mut i2c = I2cMock::new(&expectations);
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+
+    for register in [Register::DATA_FORMAT, Register::DEVID, Register::INT_SOURCE].iter() {
+        let err = adxl343.write_register(*register, 0).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::Param);
+    }
+
+    i2c.done();
+}
+
+#[test]
+fn read_registers_reads_block_in_single_transaction() {
+    let block = vec![1, 2, 3, 4, 5, 6];
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.push(Transaction::write_read(
+        ADDRESS,
+        vec![Register::THRESH_ACT.addr()],
+        block.clone(),
+    ));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    let mut buf = [0u8; 6];
+    adxl343
+        .read_registers(Register::THRESH_ACT, &mut buf)
+        .unwrap();
+    assert_eq!(buf.to_vec(), block);
+
+    // FIFO_CTL and FIFO_STATUS are the last registers
+    let err = adxl343
+        .read_registers(Register::FIFO_CTL, &mut [0u8; 3])
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::Param);
+
+    i2c.done();
+}
+
+#[test]
+fn poll_bumps_counts_bumps_since_last_call_and_rejects_bounce() {
+    let int_source = |source: u8| {
+        Transaction::write_read(ADDRESS, vec![Register::INT_SOURCE.addr()], vec![source])
+    };
+
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.extend(vec![
+        // 1g at 62.5 mg/LSB
+        Transaction::write(ADDRESS, vec![Register::THRESH_ACT.addr(), 16]),
+        Transaction::write_read(ADDRESS, vec![Register::ACT_INACT_CTL.addr()], vec![0]),
+        // AC-coupled, Z axis
+        Transaction::write(ADDRESS, vec![Register::ACT_INACT_CTL.addr(), 0b1001_0000]),
+        Transaction::write_read(ADDRESS, vec![Register::INT_ENABLE.addr()], vec![0]),
+        Transaction::write(ADDRESS, vec![Register::INT_ENABLE.addr(), 0b0001_0000]),
+        int_source(0),
+        // bump, bounce, quiet, bump
+        int_source(0b0001_0000),
+        int_source(0b0001_0000),
+        int_source(0),
+        int_source(0b0001_0000),
+    ]);
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    adxl343.configure_bump_counter(1.0, 50_000).unwrap();
+
+    assert_eq!(adxl343.poll_bumps(0).unwrap(), 1);
+    assert_eq!(adxl343.poll_bumps(10_000).unwrap(), 0);
+    assert_eq!(adxl343.poll_bumps(100_000).unwrap(), 0);
+    assert_eq!(adxl343.poll_bumps(200_000).unwrap(), 1);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_raw_reads_data_registers_lsb_first() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.push(data_bytes_transaction([0x01, 0x00, 0xFF, 0xFF, 0x00, 0x01]));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    let raw: I16x3 = adxl343.accel_raw().unwrap();
+    assert_eq!((raw.x, raw.y, raw.z), (1, -1, 256));
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn sample_rate_reads_bw_rate_ignoring_low_power() {
+    let bw_rate =
+        |value: u8| Transaction::write_read(ADDRESS, vec![Register::BW_RATE.addr()], vec![value]);
+
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    // 3200 Hz, then 100 Hz with the LOW_POWER bit set
+    expectations.extend(vec![bw_rate(0x0F), bw_rate(0x1A)]);
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    assert_eq!(adxl343.sample_rate().unwrap(), 3200.0);
+    assert_eq!(adxl343.sample_rate().unwrap(), 100.0);
+    assert_eq!(adxl343.data_rate(), DataRate::HZ_100);
+
+    i2c.done();
+}
+
+#[test]
+fn reset_reapplies_initial_configuration() {
+    // The cached data format is rewritten
+    let data_format = DataFormatFlags::FULL_RES;
+    let mut expectations = init_transactions(data_format);
+    expectations.extend(configure_transactions(data_format));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), data_format).unwrap();
+    adxl343.reset().unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn builder_writes_data_format_once_with_interrupt_polarity() {
+    let interrupts = InterruptConfig {
+        enabled: Interrupts::SINGLE_TAP,
+        int2_routed: Interrupts::SINGLE_TAP,
+        active_low: true,
+    };
+
+    let expectations = [
+        Transaction::write_read(ADDRESS, vec![Register::DEVID.addr()], vec![DEVICE_ID]),
+        Transaction::write(ADDRESS, vec![Register::POWER_CTL.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::INT_ENABLE.addr(), 0]),
+        Transaction::write(
+            ADDRESS,
+            vec![
+                Register::DATA_FORMAT.addr(),
+                DataFormatFlags::INT_INVERT.bits(),
+            ],
+        ),
+        Transaction::write_read(ADDRESS, vec![Register::BW_RATE.addr()], vec![0x0A]),
+        Transaction::write(ADDRESS, vec![Register::BW_RATE.addr(), 0x0A]),
+        Transaction::write(ADDRESS, vec![Register::THRESH_TAP.addr(), 20]),
+        Transaction::write(ADDRESS, vec![Register::DUR.addr(), 50]),
+        Transaction::write(ADDRESS, vec![Register::LATENT.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::WINDOW.addr(), 0]),
+        Transaction::write(ADDRESS, vec![Register::TAP_AXES.addr(), 0b0000_0111]),
+        Transaction::write(ADDRESS, vec![Register::INT_MAP.addr(), 0x40]),
+        Transaction::write(ADDRESS, vec![Register::INT_ENABLE.addr(), 0x40]),
+        Transaction::write(ADDRESS, vec![Register::POWER_CTL.addr(), 0x08]),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+
+    Adxl343Builder::new()
+        .interrupts(interrupts)
+        .build(i2c.clone())
+        .unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn set_low_power_rejects_unsupported_rate_without_writing() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    // 3200 Hz, which doesn't support low power mode
+    expectations.push(Transaction::write_read(
+        ADDRESS,
+        vec![Register::BW_RATE.addr()],
+        vec![0x0F],
+    ));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    let err = adxl343.set_low_power(true).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Mode);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_norm_scales_10_bit_readings() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    // 256 LSB/g at ±2g: 1g, -2g, 0g
+    expectations.push(data_transaction(256, -512, 0));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    assert_g(adxl343.accel_norm().unwrap(), [1.0, -2.0, 0.0]);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_norm_scales_full_resolution_readings() {
+    let data_format =
+        DataFormatFlags::FULL_RES | DataFormatFlags::RANGE_HI | DataFormatFlags::RANGE_LO;
+    let mut expectations = init_transactions(data_format);
+    // 256 LSB/g at every range: 1g, -16g, 8g
+    expectations.push(data_transaction(256, -4096, 2048));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), data_format).unwrap();
+    assert_g(adxl343.accel_norm().unwrap(), [1.0, -16.0, 8.0]);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_norm_scales_10_bit_readings_by_range() {
+    let data_format = DataFormatFlags::RANGE_HI;
+    let mut expectations = init_transactions(data_format);
+    // 64 LSB/g at ±8g: 1g, -8g, 4g
+    expectations.push(data_transaction(64, -512, 256));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), data_format).unwrap();
+    assert_g(adxl343.accel_norm().unwrap(), [1.0, -8.0, 4.0]);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_norm_mean_averages_readings_and_retries_bus_errors() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.extend(vec![
+        data_transaction(256, 0, -256),
+        data_transaction(0, 0, 0).with_error(I2cErrorKind::Bus),
+        data_transaction(0, 512, -256),
+    ]);
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    adxl343.set_retries(1);
+    assert_g(adxl343.accel_norm_mean(2).unwrap(), [0.5, 1.0, -1.0]);
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_raw_with_endian_rejects_left_justified_data_without_reading() {
+    let expectations = init_transactions(DataFormatFlags::JUSTIFY);
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), DataFormatFlags::JUSTIFY).unwrap();
+    assert!(matches!(
+        adxl343.accel_raw_with_endian(Endian::Little),
+        Err(Adxl343Error::JustifyMismatch)
+    ));
+    assert_eq!(
+        RawAccelerometer::<I16x3>::accel_raw(&mut adxl343)
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::Mode
+    );
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_raw_full_res_reads_13_bit_counts() {
+    let data_format =
+        DataFormatFlags::FULL_RES | DataFormatFlags::RANGE_HI | DataFormatFlags::RANGE_LO;
+    let mut expectations = init_transactions(data_format);
+    // ±16g at 256 LSB/g
+    expectations.push(data_transaction(4096, -4096, 0));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), data_format).unwrap();
+    let raw = adxl343.accel_raw_full_res().unwrap();
+    assert_eq!((raw.x, raw.y, raw.z), (4096, -4096, 0));
+
+    i2c.done();
+}
+
+#[cfg(feature = "i16x3")]
+#[test]
+fn accel_raw_full_res_rejects_10_bit_mode_without_reading() {
+    let expectations = init_transactions(DataFormatFlags::default());
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    let err = adxl343.accel_raw_full_res().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Mode);
+
+    i2c.done();
+}
+
+#[cfg(feature = "u16x3")]
+#[test]
+fn accel_raw_reads_left_justified_readings() {
+    let mut expectations = init_transactions(DataFormatFlags::JUSTIFY);
+    // 10-bit ±2g readings of 1g, -1g, and 0g shifted left by 6 bits, least
+    // significant byte first
+    expectations.push(data_bytes_transaction([0x00, 0x40, 0x00, 0xC0, 0x00, 0x00]));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new_with_data_format(i2c.clone(), DataFormatFlags::JUSTIFY).unwrap();
+    let raw: U16x3 = adxl343.accel_raw().unwrap();
+    assert_eq!((raw.x, raw.y, raw.z), (0x4000, 0xC000, 0));
+
+    i2c.done();
+}
+
+#[test]
+fn set_tap_threshold_mg_rounds_and_rejects_values_beyond_register() {
+    let mut expectations = init_transactions(DataFormatFlags::default());
+    expectations.push(Transaction::write(
+        ADDRESS,
+        vec![Register::THRESH_TAP.addr(), 16],
+    ));
+    expectations.push(Transaction::write(
+        ADDRESS,
+        vec![Register::THRESH_TAP.addr(), 0xFF],
+    ));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+    adxl343.set_tap_threshold_mg(1000).unwrap();
+    adxl343.set_tap_threshold_mg(15_968).unwrap();
+    let err = adxl343.set_tap_threshold_mg(15_969).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Param);
+
+    i2c.done();
+}
+
+#[test]
+fn new_with_retries_retries_failed_transactions() {
+    let mut expectations = vec![
+        Transaction::write_read(ADDRESS, vec![Register::DEVID.addr()], vec![DEVICE_ID])
+            .with_error(I2cErrorKind::Bus),
+        Transaction::write_read(ADDRESS, vec![Register::DEVID.addr()], vec![DEVICE_ID]),
+    ];
+    expectations.extend(configure_transactions(DataFormatFlags::default()));
+    let mut i2c = I2cMock::new(&expectations);
+
+    let adxl343 = Adxl343::new_with_retries(i2c.clone(), DataFormatFlags::default(), 1).unwrap();
+    assert_eq!(adxl343.retries(), 1);
+
+    i2c.done();
+}
+
+#[test]
+fn enable_double_tap_rejects_zero_values_without_bus_access() {
+    let expectations = init_transactions(DataFormatFlags::default());
+    let mut i2c = I2cMock::new(&expectations);
+    let mut adxl343 = Adxl343::new(i2c.clone()).unwrap();
+
+    let configs = [
+        DoubleTapConfig {
+            threshold: 0,
+            ..DoubleTapConfig::default()
+        },
+        DoubleTapConfig {
+            duration: 0,
+            ..DoubleTapConfig::default()
+        },
+        DoubleTapConfig {
+            latency: 0,
+            ..DoubleTapConfig::default()
+        },
+        DoubleTapConfig {
+            window: 0,
+            ..DoubleTapConfig::default()
+        },
+    ];
+
+    for config in configs.iter() {
+        let err = adxl343
+            .enable_double_tap(*config, IntPin::Int1)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::Param);
+    }
+
+    i2c.done();
+}